@@ -18,6 +18,35 @@ use ink_lang as ink;
 
 pub const TOKENID_INIT: u32 = 188;
 pub const MATEDATA_INIT: u32 = 20;
+/// Bytes of the zero address, which stands for "no account" and can never hold tokens.
+pub const ZERO_ADDRESS: [u8; 32] = [0x0; 32];
+/// Selector of the receiver's `on_erc721_received` message, which is also
+/// the magic value it has to return in order to accept a token.
+pub const ON_ERC721_RECEIVED: [u8; 4] = [0x15, 0x0B, 0x7A, 0x02];
+/// Selector of the receiver's `on_token_transfer` message, called by `transfer_and_call`.
+pub const ON_TOKEN_TRANSFER: [u8; 4] = [0xA4, 0xC0, 0xED, 0x36];
+/// Selector of the `transfer(destination, id)` message of an ink! NFT contract like this one,
+/// called by `rescue_nft`.
+pub const TRANSFER_SELECTOR: [u8; 4] = [0x84, 0xA1, 0x5D, 0xA1];
+/// Royalties are expressed in basis points of this denominator.
+pub const ROYALTY_DENOMINATOR: u16 = 10_000;
+/// The most entries a single query returns, keeping its output within the contract limits.
+pub const MAX_PAGE: u32 = 100;
+/// The longest memo `transfer_with_memo` accepts, in bytes.
+pub const MAX_MEMO_LEN: usize = 64;
+/// The most attributes a single token can have.
+pub const MAX_ATTRIBUTES: usize = 32;
+/// The longest attribute key or value `set_attribute` accepts, in bytes.
+pub const MAX_ATTRIBUTE_LEN: usize = 64;
+/// ERC-165 interface IDs of the standards this contract implements:
+/// ERC-165, ERC-721, ERC-721 Metadata, ERC-721 Enumerable and ERC-2981.
+pub const SUPPORTED_INTERFACES: [[u8; 4]; 5] = [
+    [0x01, 0xff, 0xc9, 0xa7],
+    [0x80, 0xac, 0x58, 0xcd],
+    [0x5b, 0x5e, 0x13, 0x9f],
+    [0x78, 0x0e, 0x9d, 0x63],
+    [0x2a, 0x55, 0x20, 0x5a],
+];
 
 #[ink::contract]
 mod baseNFT {
@@ -25,12 +54,20 @@ mod baseNFT {
     use ink_storage::collections::{
         hashmap::Entry,
         HashMap as StorageHashMap,
+        Vec as StorageVec,
+    };
+    use ink_prelude::{
+        string::{
+            String,
+            ToString,
+        },
+        vec::Vec,
     };
     use scale::{
         Decode,
         Encode,
     };
-    use crate::{TOKENID_INIT,MATEDATA_INIT};
+    use crate::{TOKENID_INIT,MATEDATA_INIT,ZERO_ADDRESS,ON_ERC721_RECEIVED,ROYALTY_DENOMINATOR,SUPPORTED_INTERFACES,MAX_PAGE,MAX_MEMO_LEN,MAX_ATTRIBUTES,MAX_ATTRIBUTE_LEN};
 
     /// A token ID.
     pub type TokenId = u32;
@@ -38,15 +75,107 @@ mod baseNFT {
     #[ink(storage)]
     #[derive(Default)]
     pub struct Simple_NFT {
+        /// The contract owner, allowed to perform administrative operations.
+        owner: AccountId,
+        /// The account `transfer_ownership` nominated, until it accepts.
+        pending_owner: Option<AccountId>,
+        /// Accounts allowed to mint besides the contract owner.
+        minters: StorageHashMap<AccountId, bool>,
+        /// Mapping from account to the number of tokens it may still mint through `allowlist_mint`.
+        allowlist: StorageHashMap<AccountId, u32>,
+        /// Whether transfers are currently blocked.
+        transfers_paused: bool,
+        /// Whether minting is currently blocked.
+        mints_paused: bool,
+        /// Whether approvals are currently blocked.
+        approvals_paused: bool,
+        /// The account receiving royalties by default.
+        royalty_receiver: AccountId,
+        /// The default royalty in basis points of the sale price.
+        royalty_basis_points: u16,
+        /// mapping from token to its own royalty receiver and basis points
+        token_royalty: StorageHashMap<TokenId, (AccountId, u16)>,
+        /// Whether only whitelisted marketplaces can move tokens on behalf of their owners.
+        enforce_royalties: bool,
+        /// Marketplaces allowed to move tokens while royalties are enforced.
+        marketplace_whitelist: StorageHashMap<AccountId, bool>,
+        /// The fee `transfer` and `transfer_from` require, if any.
+        transfer_fee: Balance,
+        /// The account receiving the transfer fees.
+        treasury: AccountId,
+        /// The maximum number of tokens that can exist, if capped.
+        max_supply: Option<u32>,
+        /// The first block in which minting is possible, if limited.
+        mint_start_block: Option<BlockNumber>,
+        /// The last block in which minting is possible, if limited.
+        mint_end_block: Option<BlockNumber>,
+        /// The number of tokens ever minted, burned ones included.
+        total_minted: u32,
+        /// The ID `mint_next` tries first.
+        next_token_id: TokenId,
+        /// The name of the collection.
+        name: Option<String>,
+        /// The symbol of the collection.
+        symbol: Option<String>,
         /// Mapping from token to owner.
         token_owner: StorageHashMap<TokenId, AccountId>,
         /// Mapping from owner to number of owned token.
         owned_tokens_count: StorageHashMap<AccountId, u32>,
         /// mapping from token to matedata
         matedatas: StorageHashMap<TokenId, u32>,
+        /// Mapping from metadata to the token it was last set on.
+        metadata_to_token: StorageHashMap<u32, TokenId>,
+        /// mapping from token to its URI (e.g. an IPFS CID or HTTP URL)
+        token_uris: StorageHashMap<TokenId, String>,
+        /// Mapping from (token, key) to the value of that on-chain attribute.
+        attributes: StorageHashMap<(TokenId, String), String>,
+        /// Mapping from token to the keys of its attributes, ordered by key.
+        attribute_keys: StorageHashMap<TokenId, Vec<String>>,
+        /// The URI prefix for tokens without their own URI.
+        base_uri: Option<String>,
+        /// Whether token URIs can no longer be changed.
+        metadata_frozen: bool,
+        /// Tokens whose URI, metadata and attributes can no longer be changed.
+        token_metadata_frozen: StorageHashMap<TokenId, bool>,
+        /// Mapping from (owner, index) to the owner's token at that index.
+        owned_tokens: StorageHashMap<(AccountId, u32), TokenId>,
+        /// mapping from token to its position in the owner's `owned_tokens`
+        owned_tokens_index: StorageHashMap<TokenId, u32>,
+        /// All existing tokens, used for enumeration.
+        all_tokens: StorageVec<TokenId>,
+        /// mapping from token to its position in `all_tokens`
+        all_tokens_index: StorageHashMap<TokenId, u32>,
         /// mapping from token to approvals user
-        /// (owner,tokenid) -> user
-        approvals_token: StorageHashMap<(AccountId, TokenId), AccountId>,
+        /// (owner,tokenid) -> (user, expiry timestamp)
+        approvals_token: StorageHashMap<(AccountId, TokenId), (AccountId, u64)>,
+        /// How `approve` treats a token that is already approved.
+        approval_mode: ApprovalMode,
+        /// Mapping from owner and token to the accounts approved for it in `ApprovalMode::Multi`.
+        approvals_token_multi: StorageHashMap<(AccountId, TokenId), Vec<AccountId>>,
+        /// Mapping from owner to operator approvals and their expiry timestamp.
+        operator_approvals: StorageHashMap<(AccountId, AccountId), (bool, u64)>,
+        /// Mapping from token to the account it is locked to.
+        locked: StorageHashMap<TokenId, AccountId>,
+        /// Mapping from token to the vault it is wrapped in.
+        wrapped_in: StorageHashMap<TokenId, AccountId>,
+        /// Mapping from wrapped token to the account `unwrap` returns it to.
+        wrapped_by: StorageHashMap<TokenId, AccountId>,
+        /// The number of `Transfer` events emitted so far.
+        transfer_count: u64,
+        /// Tokens that can never be transferred or approved.
+        soulbound: StorageHashMap<TokenId, bool>,
+        /// Mapping from token to the timestamp until which its owner locked it.
+        timelocks: StorageHashMap<TokenId, u64>,
+        /// Mapping from token to the price it is listed for.
+        listings: StorageHashMap<TokenId, Balance>,
+        /// Mapping from token to the number of copies it represents, if not one.
+        quantity: StorageHashMap<TokenId, u32>,
+        /// Mapping from token to the account that minted it, kept across transfers.
+        original_minter: StorageHashMap<TokenId, AccountId>,
+        /// Accounts that may neither receive nor transfer tokens.
+        denylist: StorageHashMap<AccountId, bool>,
+        /// Whether a receiver is being called, during which no token can be moved or burned.
+        reentrancy_locked: bool,
     }
 
     #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
@@ -60,6 +189,60 @@ mod baseNFT {
         CannotRemove,
         CannotFetchValue,
         NotAllowed,
+        BalanceUnderflow,
+        NotReceiver,
+        NotContractOwner,
+        Paused,
+        InvalidRoyalty,
+        MaxSupplyReached,
+        MetadataFrozen,
+        BalanceOverflow,
+        TokenLocked,
+        Soulbound,
+        Reentrancy,
+        InvalidUri,
+        NotForSale,
+        InsufficientPayment,
+        PaymentFailed,
+        Denied,
+        RescueFailed,
+        MintNotOpen,
+        ApprovalChanged,
+    }
+
+    /// Everything about a single token, as returned by `token_info`.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TokenInfo {
+        pub owner: Option<AccountId>,
+        pub approved: Option<AccountId>,
+        pub metadata: Option<u32>,
+        pub uri: Option<String>,
+    }
+
+    /// How `approve` treats a token that already has an approved account.
+    #[derive(
+        Encode,
+        Decode,
+        Debug,
+        PartialEq,
+        Eq,
+        Copy,
+        Clone,
+        Default,
+        ink_storage::traits::SpreadLayout,
+        ink_storage::traits::PackedLayout,
+    )]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+    )]
+    pub enum ApprovalMode {
+        /// A new approval replaces the previous one.
+        #[default]
+        Overwrite,
+        /// A new approval is added to the previous ones. Approvals cannot expire.
+        Multi,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -73,6 +256,18 @@ mod baseNFT {
         id: TokenId,
     }
 
+    /// Event emitted next to `Transfer` when the contract owner moves a token.
+    #[ink(event)]
+    pub struct ForcedTransfer {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+        by: AccountId,
+    }
+
     /// Event emitted when a token approve occurs.
     #[ink(event)]
     pub struct Approval {
@@ -95,342 +290,6347 @@ mod baseNFT {
         approved: bool,
     }
 
+    /// Event emitted when a listed token is bought.
+    #[ink(event)]
+    pub struct Sold {
+        #[ink(topic)]
+        id: TokenId,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        price: Balance,
+    }
+
+    /// Event emitted when the contract ownership is transferred.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    /// Event emitted by `transfer_with_memo`, next to its `Transfer` event.
+    #[ink(event)]
+    pub struct TransferMemo {
+        #[ink(topic)]
+        id: TokenId,
+        memo: Vec<u8>,
+    }
+
+    /// Event emitted when the contract owner withdraws funds held by the contract.
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a new contract owner is nominated, before it accepts.
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    /// Event emitted when the contract gets paused.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the contract gets unpaused.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when a token is minted, next to its `Transfer` event.
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        to: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+        metadata: u32,
+    }
+
+    /// Event emitted when the metadata of a token changes (EIP-4906).
+    #[ink(event)]
+    pub struct MetadataUpdate {
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    /// Event emitted when the metadata of a range of tokens changes (EIP-4906).
+    #[ink(event)]
+    pub struct BatchMetadataUpdate {
+        from_id: TokenId,
+        to_id: TokenId,
+    }
+
     impl Simple_NFT {
         /// Creates a new ERC721 token contract.
+        ///
+        /// Like every constructor except `new_with_initial_owner`, this mints the
+        /// ten initial tokens to the deployer, which also becomes the contract owner.
         #[ink(constructor)]
         pub fn new() -> Self {
+            Self::new_with_count(10)
+        }
+
+        /// Creates a new ERC721 token contract minting `count` initial tokens to the
+        /// deployer, starting at id `TOKENID_INIT`.
+        #[ink(constructor)]
+        pub fn new_with_count(count: u32) -> Self {
+            Self::new_init(None, None, None, Self::env().caller(), count)
+        }
+
+        /// Creates a new ERC721 token contract with the given collection name and symbol.
+        #[ink(constructor)]
+        pub fn new_with_metadata(name: String, symbol: String) -> Self {
+            Self::new_init(Some(name), Some(symbol), None, Self::env().caller(), 10)
+        }
+
+        /// Creates a new ERC721 token contract of which at most `max` tokens can exist.
+        #[ink(constructor)]
+        pub fn new_capped(max: u32) -> Self {
+            Self::new_init(None, None, Some(max), Self::env().caller(), 10)
+        }
+
+        /// Creates a new ERC721 token contract minting the initial tokens to `owner`.
+        /// The deployer still becomes the contract owner.
+        #[ink(constructor)]
+        pub fn new_with_initial_owner(owner: AccountId) -> Self {
+            Self::new_init(None, None, None, owner, 10)
+        }
+
+        /// Creates a new ERC721 token contract whose approvals follow `mode`.
+        #[ink(constructor)]
+        pub fn new_with_approval_mode(mode: ApprovalMode) -> Self {
+            let mut my = Self::new_init(None, None, None, Self::env().caller(), 10);
+            my.approval_mode = mode;
+            my
+        }
+
+        /// Sets up the storage and mints `count` initial tokens like `try_new_init`.
+        ///
+        /// Constructors cannot return an error, so deployment fails if that does.
+        fn new_init(
+            name: Option<String>,
+            symbol: Option<String>,
+            max_supply: Option<u32>,
+            initial_owner: AccountId,
+            count: u32,
+        ) -> Self {
+            Self::try_new_init(name, symbol, max_supply, initial_owner, count)
+                .expect("Cannot mint the initial tokens")
+        }
+
+        /// Sets up the storage and mints `count` initial tokens. Fails with
+        /// `Error::MaxSupplyReached` if `max_supply` does not allow that many and
+        /// with `Error::NotAllowed` if their ids would overflow.
+        fn try_new_init(
+            name: Option<String>,
+            symbol: Option<String>,
+            max_supply: Option<u32>,
+            initial_owner: AccountId,
+            count: u32,
+        ) -> Result<Self, Error> {
+            if matches!(max_supply, Some(max) if count > max) {
+                return Err(Error::MaxSupplyReached)
+            };
+            let next_token_id = TOKENID_INIT.checked_add(count).ok_or(Error::NotAllowed)?;
             let mut my = Self {
+                owner: Self::env().caller(),
+                pending_owner: None,
+                minters: Default::default(),
+                allowlist: Default::default(),
+                transfers_paused: false,
+                mints_paused: false,
+                approvals_paused: false,
+                royalty_receiver: AccountId::from(ZERO_ADDRESS),
+                royalty_basis_points: 0,
+                token_royalty: Default::default(),
+                enforce_royalties: false,
+                marketplace_whitelist: Default::default(),
+                transfer_fee: 0,
+                treasury: AccountId::from(ZERO_ADDRESS),
+                max_supply,
+                mint_start_block: None,
+                mint_end_block: None,
+                total_minted: 0,
+                next_token_id,
+                name,
+                symbol,
                 token_owner: Default::default(),
                 owned_tokens_count: Default::default(),
                 matedatas: Default::default(),
+                metadata_to_token: Default::default(),
+                token_uris: Default::default(),
+                attributes: Default::default(),
+                attribute_keys: Default::default(),
+                base_uri: None,
+                metadata_frozen: false,
+                token_metadata_frozen: Default::default(),
+                owned_tokens: Default::default(),
+                owned_tokens_index: Default::default(),
+                all_tokens: Default::default(),
+                all_tokens_index: Default::default(),
                 approvals_token: Default::default(),
+                approval_mode: ApprovalMode::Overwrite,
+                approvals_token_multi: Default::default(),
+                operator_approvals: Default::default(),
+                locked: Default::default(),
+                wrapped_in: Default::default(),
+                wrapped_by: Default::default(),
+                transfer_count: 0,
+                soulbound: Default::default(),
+                timelocks: Default::default(),
+                listings: Default::default(),
+                quantity: Default::default(),
+                original_minter: Default::default(),
+                denylist: Default::default(),
+                reentrancy_locked: false,
             };
-            my.inherent_init();
-            my
+            my.inherent_init(&initial_owner, count)?;
+            Ok(my)
         }
 
-        /// Returns the balance of the owner.
-        ///
-        /// This represents the amount of unique tokens the owner has.
+        /// Returns how `approve` treats a token that is already approved.
         #[ink(message)]
-        pub fn balance_of(&self, owner: AccountId) -> u32 {
-            self.balance_of_or_zero(&owner)
+        pub fn approval_mode(&self) -> ApprovalMode {
+            self.approval_mode
         }
 
-        /// Returns the owner of the token.
+        /// Returns the contract owner.
         #[ink(message)]
-        pub fn owner_of(&self, id: TokenId) -> Option<AccountId> {
-            self.token_owner.get(&id).cloned()
+        pub fn owner(&self) -> AccountId {
+            self.owner
         }
 
-        /// Returns the approved account ID for this token if any.
+        /// Returns the account nominated as the next contract owner, if any.
         #[ink(message)]
-        pub fn get_approved(&self, id: TokenId) -> Option<AccountId> {
-            let owner = self.owner_of(id);
-            self
-                .approvals_token
-                .get(&(owner.expect("Error with AccountId"),id))
-                .cloned()
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
         }
 
-        /// Returns `true` if the operator is approved by the owner.
+        /// Nominates `new_owner` as the next contract owner, replacing any earlier nomination.
+        /// The ownership only moves once `new_owner` calls `accept_ownership`.
+        /// Only the contract owner can do this.
         #[ink(message)]
-        pub fn is_approved(&self, id: TokenId, user: AccountId) -> bool {
-            self.approved_for_token(id, user)
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            if new_owner == AccountId::from(ZERO_ADDRESS) {
+                return Err(Error::NotAllowed)
+            };
+            self.pending_owner = Some(new_owner);
+            self.env().emit_event(OwnershipTransferStarted {
+                previous: self.owner,
+                new: new_owner,
+            });
+            Ok(())
         }
 
-        /// Approves the account to transfer the specified token on behalf of the caller.
-        /// the last user will be valid
+        /// Takes over the contract ownership. Only the nominated account can do this.
         #[ink(message)]
-        pub fn approve(&mut self, to: AccountId, id: TokenId) -> Result<(), Error> {
-            self.approve_for(&to, id)?;
+        pub fn accept_ownership(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::NotAllowed)
+            };
+            let previous = self.owner;
+            self.owner = caller;
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous,
+                new: caller,
+            });
             Ok(())
         }
 
-        /// Transfers the token from the caller to the given destination.
+        /// Gives up the contract ownership for good, after which no administrative
+        /// operation can be performed anymore. Only the contract owner can do this.
         #[ink(message)]
-        pub fn transfer(
-            &mut self,
-            destination: AccountId,
-            id: TokenId,
-        ) -> Result<(), Error> {
-            let caller = self.env().caller();
-            self.transfer_token_from(&caller, &destination, id,false)?;
+        pub fn renounce_ownership(&mut self) -> Result<(), Error> {
+            self.only_owner()?;
+            let previous = self.owner;
+            self.owner = AccountId::from(ZERO_ADDRESS);
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous,
+                new: self.owner,
+            });
             Ok(())
         }
 
-        /// Transfer approved or owned token.
+        /// Sends `amount` of the funds held by the contract to `to`.
+        /// Only the contract owner can do this.
         #[ink(message)]
-        pub fn transfer_from(
-            &mut self,
-            from: AccountId,
-            to: AccountId,
-            id: TokenId,
-        ) -> Result<(), Error> {
-            self.transfer_token_from(&from, &to, id,true)?;
+        pub fn withdraw(&mut self, amount: Balance, to: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            if amount > self.env().balance() {
+                return Err(Error::InsufficientPayment)
+            };
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| Error::PaymentFailed)?;
+            self.env().emit_event(Withdrawn { to, amount });
             Ok(())
         }
 
-        /// Transfers token `id` `from` the sender to the `to` AccountId.
-        fn transfer_token_from(
-            &mut self,
-            from: &AccountId,
-            to: &AccountId,
-            id: TokenId,
-            need_approval: bool,
-        ) -> Result<(), Error> {
-            let caller = self.env().caller();
-            if !self.exists(id) {
-                return Err(Error::TokenNotFound)
-            };
-            if !need_approval {
-                let owner = self.owner_of(id);
-                if !(owner == Some(caller)) {
-                    return Err(Error::NotAllowed)
-                }
+        /// Returns `true` if `who` has been granted the minter role.
+        ///
+        /// The contract owner can always mint, whether it holds the role or not.
+        #[ink(message)]
+        pub fn is_minter(&self, who: AccountId) -> bool {
+            *self.minters.get(&who).unwrap_or(&false)
+        }
+
+        /// Grants `who` the minter role. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn add_minter(&mut self, who: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            self.minters.insert(who, true);
+            Ok(())
+        }
+
+        /// Revokes the minter role from `who`. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn remove_minter(&mut self, who: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            self.minters.take(&who);
+            Ok(())
+        }
+
+        /// Returns `true` if `who` may neither receive nor transfer tokens.
+        #[ink(message)]
+        pub fn is_denied(&self, who: AccountId) -> bool {
+            self.denylist.contains_key(&who)
+        }
+
+        /// Blocks or unblocks `who` from receiving and transferring tokens.
+        /// Denying `who` also revokes every operator approval granted to it, and
+        /// unblocking does not restore them. Only the contract owner can do this.
+        ///
+        /// Finding those approvals walks all operator approvals in storage, so the
+        /// cost of denying grows with the number of approvals ever granted.
+        #[ink(message)]
+        pub fn set_denied(&mut self, who: AccountId, denied: bool) -> Result<(), Error> {
+            self.only_owner()?;
+            if denied {
+                self.denylist.insert(who, true);
+                self.revoke_operator(who);
+            } else {
+                self.denylist.take(&who);
             }
-            if need_approval && !self.approved_for_token(id,caller) {
-                return Err(Error::NotApproved)
-            };
-            self.clear_approval(id)?;
-            self.remove_token_from(from, id)?;
-            self.add_token_to(to, id)?;
-            self.env().emit_event(Transfer {
-                from: Some(*from),
-                to: Some(*to),
-                id,
-            });
             Ok(())
         }
 
-        /// Removes token `id` from the owner.
-        fn remove_token_from(
+        /// Returns `true` if only whitelisted marketplaces can move tokens on behalf of their owners.
+        #[ink(message)]
+        pub fn enforce_royalties(&self) -> bool {
+            self.enforce_royalties
+        }
+
+        /// Enables or disables royalty enforcement. While enabled, approved accounts and
+        /// operators other than whitelisted marketplaces cannot move tokens, leaving `buy`,
+        /// which pays the royalty, as the way to sell one. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_enforce_royalties(&mut self, enforce: bool) -> Result<(), Error> {
+            self.only_owner()?;
+            self.enforce_royalties = enforce;
+            Ok(())
+        }
+
+        /// Returns `true` if `marketplace` can move tokens while royalties are enforced.
+        #[ink(message)]
+        pub fn is_whitelisted_marketplace(&self, marketplace: AccountId) -> bool {
+            self.marketplace_whitelist.contains_key(&marketplace)
+        }
+
+        /// Allows or disallows `marketplace` to move tokens while royalties are enforced.
+        /// Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_whitelisted_marketplace(
             &mut self,
-            from: &AccountId,
-            id: TokenId,
+            marketplace: AccountId,
+            whitelisted: bool,
         ) -> Result<(), Error> {
-            let Self {
-                token_owner,
-                owned_tokens_count,
-                ..
-            } = self;
-            let occupied = match token_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::TokenNotFound),
-                Entry::Occupied(occupied) => occupied,
-            };
-            decrease_counter_of(owned_tokens_count, from)?;
-            occupied.remove_entry();
+            self.only_owner()?;
+            if whitelisted {
+                self.marketplace_whitelist.insert(marketplace, true);
+            } else {
+                self.marketplace_whitelist.take(&marketplace);
+            }
             Ok(())
         }
 
-        /// Adds the token `id` to the `to` AccountID.
-        fn add_token_to(&mut self, to: &AccountId, id: TokenId) -> Result<(), Error> {
-            let Self {
-                token_owner,
-                owned_tokens_count,
-                ..
-            } = self;
-            let vacant_token_owner = match token_owner.entry(id) {
-                Entry::Vacant(vacant) => vacant,
-                Entry::Occupied(_) => return Err(Error::TokenExists),
-            };
-            if *to == AccountId::from([0x0; 32]) {
-                return Err(Error::NotAllowed)
-            };
-            let entry = owned_tokens_count.entry(*to);
-            increase_counter_of(entry);
-            vacant_token_owner.insert(*to);
-            Ok(())
+        /// Returns the fee `transfer` and `transfer_from` require and the account receiving it.
+        #[ink(message)]
+        pub fn transfer_fee(&self) -> (Balance, AccountId) {
+            (self.transfer_fee, self.treasury)
         }
 
-        /// Approve the passed AccountId to transfer the specified token on behalf of the message's sender.
-        fn approve_for(&mut self, to: &AccountId, id: TokenId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let owner = self.owner_of(id);
-            if !(owner == Some(caller)) {
-                return Err(Error::NotAllowed)
-            };
-            if *to == AccountId::from([0x0; 32]) {
+        /// Requires `fee` to be paid along with every transfer of a token, including
+        /// sales through `buy`, forwarded to `treasury`. A zero fee disables it.
+        /// Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_transfer_fee(&mut self, fee: Balance, treasury: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            if fee > 0 && treasury == AccountId::from(ZERO_ADDRESS) {
                 return Err(Error::NotAllowed)
             };
-
-            self.approvals_token.insert((owner.expect("Error with AccountId"),id), *to);
-            self.env().emit_event(Approval {
-                from: caller,
-                to: *to,
-                id,
-            });
+            self.transfer_fee = fee;
+            self.treasury = treasury;
             Ok(())
         }
 
-        /// Removes existing approval from token `id`.
-        fn clear_approval(&mut self, id: TokenId) -> Result<(), Error> {
-            let owner = self.owner_of(id);
-            if !self.approvals_token.contains_key(&(owner.expect("Error with AccountId"),id)) {
-                return Ok(())
-            };
-            match self.approvals_token.take(&(owner.expect("Error with AccountId"),id)) {
-                Some(_res) => Ok(()),
-                None => Err(Error::CannotRemove),
-            }
+        /// Returns `true` if transfers, minting or approvals are paused.
+        #[ink(message)]
+        pub fn paused(&self) -> bool {
+            self.transfers_paused || self.mints_paused || self.approvals_paused
         }
 
-        /// inherent initialization a NFT token (max 5 nft token)
-        fn inherent_init(&mut self) {
-            let caller = self.env().caller();
-            for i in 0..10 {
-                self.add_token_to(&caller,TOKENID_INIT+i);
-                self.matedatas.insert(TOKENID_INIT+i, MATEDATA_INIT+i);
-            }
+        /// Returns `true` if transfers are paused.
+        #[ink(message)]
+        pub fn transfers_paused(&self) -> bool {
+            self.transfers_paused
         }
-        /// Returns the total number of tokens from an account.
-        fn balance_of_or_zero(&self, of: &AccountId) -> u32 {
-            *self.owned_tokens_count.get(of).unwrap_or(&0)
+
+        /// Returns `true` if minting is paused.
+        #[ink(message)]
+        pub fn mints_paused(&self) -> bool {
+            self.mints_paused
         }
 
-        /// check the approved for the user
-        fn approved_for_token(&self,id: TokenId,user: AccountId) -> bool {
-            if user == AccountId::from([0x0; 32]) {
-                return false
-            }
-            let owner = self.owner_of(id);
-            user == *self
-                .approvals_token
-                .get(&(owner.expect("Error with AccountId"),id))
-                .unwrap_or(&AccountId::from([0x0; 32]))
+        /// Returns `true` if approvals are paused.
+        #[ink(message)]
+        pub fn approvals_paused(&self) -> bool {
+            self.approvals_paused
         }
-        /// Returns true if token `id` exists or false if it does not.
-        fn exists(&self, id: TokenId) -> bool {
-            self.token_owner.get(&id).is_some() && self.token_owner.contains_key(&id)
+
+        /// Blocks or unblocks transfers, listings and locks. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_transfers_paused(&mut self, paused: bool) -> Result<(), Error> {
+            self.only_owner()?;
+            self.transfers_paused = paused;
+            Ok(())
         }
-    }
 
-    fn decrease_counter_of(
-        hmap: &mut StorageHashMap<AccountId, u32>,
+        /// Blocks or unblocks minting. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_mints_paused(&mut self, paused: bool) -> Result<(), Error> {
+            self.only_owner()?;
+            self.mints_paused = paused;
+            Ok(())
+        }
+
+        /// Blocks or unblocks approvals. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_approvals_paused(&mut self, paused: bool) -> Result<(), Error> {
+            self.only_owner()?;
+            self.approvals_paused = paused;
+            Ok(())
+        }
+
+        /// Pauses the contract, blocking transfers, approvals and minting.
+        /// Only the contract owner can do this.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), Error> {
+            self.only_owner()?;
+            self.set_all_paused(true);
+            self.env().emit_event(Paused {
+                account: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Unpauses transfers, approvals and minting. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), Error> {
+            self.only_owner()?;
+            self.set_all_paused(false);
+            self.env().emit_event(Unpaused {
+                account: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Returns the royalty receiver and the royalty amount due for selling token `id`
+        /// at `sale_price`, as specified by ERC-2981.
+        ///
+        /// A royalty set for the token takes precedence over the default royalty.
+        #[ink(message)]
+        pub fn royalty_info(&self, id: TokenId, sale_price: Balance) -> (AccountId, Balance) {
+            let (receiver, basis_points) = self
+                .token_royalty
+                .get(&id)
+                .cloned()
+                .unwrap_or((self.royalty_receiver, self.royalty_basis_points));
+            (receiver, royalty_amount(sale_price, basis_points))
+        }
+
+        /// Sets the royalty paid to `receiver` for every token, in basis points.
+        /// Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_default_royalty(
+            &mut self,
+            receiver: AccountId,
+            basis_points: u16,
+        ) -> Result<(), Error> {
+            self.only_owner()?;
+            if basis_points > ROYALTY_DENOMINATOR {
+                return Err(Error::InvalidRoyalty)
+            };
+            self.royalty_receiver = receiver;
+            self.royalty_basis_points = basis_points;
+            Ok(())
+        }
+
+        /// Sets the royalty paid to `receiver` for token `id`, in basis points.
+        /// Only the token owner or the contract owner can do this.
+        #[ink(message)]
+        pub fn set_token_royalty(
+            &mut self,
+            id: TokenId,
+            receiver: AccountId,
+            basis_points: u16,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.exists(id) {
+                return Err(Error::TokenNotFound)
+            };
+            if self.owner_of(id) != Some(caller) && caller != self.owner {
+                return Err(Error::NotOwner)
+            };
+            if basis_points > ROYALTY_DENOMINATOR {
+                return Err(Error::InvalidRoyalty)
+            };
+            self.token_royalty.insert(id, (receiver, basis_points));
+            Ok(())
+        }
+
+        /// Returns the name of the collection if any.
+        #[ink(message)]
+        pub fn name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        /// Returns the symbol of the collection if any.
+        #[ink(message)]
+        pub fn symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        /// Returns the balance of the owner.
+        ///
+        /// This represents the amount of unique tokens the owner has.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> u32 {
+            self.balance_of_or_zero(&owner)
+        }
+
+        /// Returns the number of tokens the owner holds, as stored.
+        ///
+        /// Unlike `balance_of`, this is never meant to change meaning, e.g. to
+        /// leave out locked tokens.
+        #[ink(message)]
+        pub fn raw_token_count(&self, owner: AccountId) -> u32 {
+            self.owned_tokens_count.get(&owner).cloned().unwrap_or(0)
+        }
+
+        /// Returns the owner of the token.
+        #[ink(message)]
+        pub fn owner_of(&self, id: TokenId) -> Option<AccountId> {
+            self.token_owner.get(&id).cloned()
+        }
+
+        /// Returns the owner of the token, failing with `Error::TokenNotFound` if it does not exist.
+        #[ink(message)]
+        pub fn try_owner_of(&self, id: TokenId) -> Result<AccountId, Error> {
+            self.owner_of(id).ok_or(Error::TokenNotFound)
+        }
+
+        /// Returns `true` if the contract implements the ERC-165 interface `interface_id`.
+        #[ink(message)]
+        pub fn supports_interface(&self, interface_id: [u8; 4]) -> bool {
+            SUPPORTED_INTERFACES.contains(&interface_id)
+        }
+
+        /// Returns `true` if token `id` exists.
+        #[ink(message)]
+        pub fn token_exists(&self, id: TokenId) -> bool {
+            self.exists(id)
+        }
+
+        /// Returns the number of existing tokens.
+        #[ink(message)]
+        pub fn total_supply(&self) -> u32 {
+            self.all_tokens.len()
+        }
+
+        /// Returns the number of tokens ever minted. Unlike `total_supply`,
+        /// this does not go down when tokens are burned.
+        #[ink(message)]
+        pub fn total_minted(&self) -> u32 {
+            self.total_minted
+        }
+
+        /// Returns `(total_supply, distinct_owners, total_approvals)`.
+        #[ink(message)]
+        pub fn stats(&self) -> (u32, u32, u32) {
+            let owners = self.owned_tokens_count.values().filter(|count| **count > 0).count();
+            let multi: usize = self.approvals_token_multi.values().map(|approved| approved.len()).sum();
+            (self.total_supply(), owners as u32, self.approvals_token.len() + multi as u32)
+        }
+
+        /// Returns the number of `Transfer` events emitted, including mints and burns.
+        #[ink(message)]
+        pub fn transfer_count(&self) -> u64 {
+            self.transfer_count
+        }
+
+        /// Returns the token at `index` of all existing tokens if any.
+        ///
+        /// Together with `total_supply` this allows to enumerate all tokens.
+        /// The order is not specified and changes when tokens get burned.
+        #[ink(message)]
+        pub fn token_by_index(&self, index: u32) -> Option<TokenId> {
+            self.all_tokens.get(index).cloned()
+        }
+
+        /// Returns the token at `index` of the tokens owned by `owner` if any.
+        ///
+        /// Together with `balance_of` this allows to enumerate the tokens of an owner.
+        /// The order is not specified and changes when the owner loses a token.
+        #[ink(message)]
+        pub fn token_of_owner_by_index(&self, owner: AccountId, index: u32) -> Option<TokenId> {
+            self.owned_tokens.get(&(owner, index)).cloned()
+        }
+
+        /// Returns the tokens owned by `owner`, in the order of `token_of_owner_by_index`.
+        ///
+        /// Only the first `MAX_PAGE` tokens are returned; use `tokens_of_owner_paged`
+        /// for owners holding more.
+        #[ink(message)]
+        pub fn tokens_of_owner(&self, owner: AccountId) -> Vec<TokenId> {
+            self.owned_tokens_from(owner, 0, MAX_PAGE)
+        }
+
+        /// Returns up to `limit`, but at most `MAX_PAGE`, tokens owned by `owner`
+        /// in the order of `token_of_owner_by_index`, skipping the first `start`.
+        #[ink(message)]
+        pub fn tokens_of_owner_paged(&self, owner: AccountId, start: u32, limit: u32) -> Vec<TokenId> {
+            self.owned_tokens_from(owner, start, limit.min(MAX_PAGE))
+        }
+
+        /// Returns up to `limit`, but at most `MAX_PAGE`, `(token, owner)` pairs in the order
+        /// of `token_by_index`, not by token ID, skipping the first `start`, for copying the
+        /// ownership to another contract. Burning tokens between two pages changes that order.
+        #[ink(message)]
+        pub fn export_owners(&self, start: u32, limit: u32) -> Vec<(TokenId, AccountId)> {
+            self.all_tokens_from(start, limit.min(MAX_PAGE))
+                .into_iter()
+                .filter_map(|id| self.owner_of(id).map(|owner| (id, owner)))
+                .collect()
+        }
+
+        /// Returns up to `limit`, but at most `MAX_PAGE`, `(token, metadata)` pairs in the order
+        /// of `token_by_index`, not by token ID, skipping the first `start`. Tokens without
+        /// metadata report `0`. Burning tokens between two pages changes that order.
+        #[ink(message)]
+        pub fn tokens_with_metadata(&self, start: u32, limit: u32) -> Vec<(TokenId, u32)> {
+            self.all_tokens_from(start, limit.min(MAX_PAGE))
+                .into_iter()
+                .map(|id| (id, self.matedatas.get(&id).cloned().unwrap_or(0)))
+                .collect()
+        }
+
+        /// Returns the token count of each account in `owners`, in order.
+        #[ink(message)]
+        pub fn balances_of(&self, owners: Vec<AccountId>) -> Vec<u32> {
+            owners.iter().map(|owner| self.balance_of_or_zero(owner)).collect()
+        }
+
+        /// Returns the owner of each token in `ids`, in order, or `None` if it does not exist.
+        #[ink(message)]
+        pub fn owners_of(&self, ids: Vec<TokenId>) -> Vec<Option<AccountId>> {
+            ids.into_iter().map(|id| self.owner_of(id)).collect()
+        }
+
+        /// Returns the approved account ID for this token if any and not expired.
+        /// In `ApprovalMode::Multi`, this is the account approved first.
+        #[ink(message)]
+        pub fn get_approved(&self, id: TokenId) -> Option<AccountId> {
+            self.get_approvals(id).first().cloned()
+        }
+
+        /// Returns every account approved for this token and not expired, in the order
+        /// they were approved. Holds at most one account in `ApprovalMode::Overwrite`.
+        #[ink(message)]
+        pub fn get_approvals(&self, id: TokenId) -> Vec<AccountId> {
+            let owner = match self.owner_of(id) {
+                Some(owner) => owner,
+                None => return Vec::new(),
+            };
+            let now = self.env().block_timestamp();
+            self.raw_approvals(&owner, id)
+                .into_iter()
+                .filter(|(_, deadline)| now <= *deadline)
+                .map(|(user, _)| user)
+                .collect()
+        }
+
+        /// Returns up to `limit`, but at most `MAX_PAGE`, unexpired `(token, approved account)`
+        /// pairs `owner` has granted in the order of `token_of_owner_by_index`, skipping the first `start`.
+        #[ink(message)]
+        pub fn approvals_of_owner(
+            &self,
+            owner: AccountId,
+            start: u32,
+            limit: u32,
+        ) -> Vec<(TokenId, AccountId)> {
+            (0..self.balance_of_or_zero(&owner))
+                .filter_map(|index| self.owned_tokens.get(&(owner, index)).cloned())
+                .flat_map(|id| self.get_approvals(id).into_iter().map(move |approved| (id, approved)))
+                .skip(start as usize)
+                .take(limit.min(MAX_PAGE) as usize)
+                .collect()
+        }
+
+        /// Returns `true` if the operator is approved by the owner.
+        #[ink(message)]
+        pub fn is_approved(&self, id: TokenId, user: AccountId) -> bool {
+            self.approved_for_token(id, user)
+        }
+
+        /// Returns `true` if the operator is approved for all tokens of the owner.
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.approved_for_all(owner, operator)
+        }
+
+        /// Returns `true` if `who` owns token `id`, is approved for it or is an
+        /// approved operator of its owner.
+        #[ink(message)]
+        pub fn is_owner_or_approved(&self, who: AccountId, id: TokenId) -> bool {
+            match self.owner_of(id) {
+                Some(owner) => {
+                    owner == who
+                        || self.approved_for_token(id, who)
+                        || self.approved_for_all(owner, who)
+                }
+                None => false,
+            }
+        }
+
+        /// Approves or disapproves the operator for all tokens of the caller.
+        #[ink(message)]
+        pub fn set_approval_for_all(
+            &mut self,
+            to: AccountId,
+            approved: bool,
+        ) -> Result<(), Error> {
+            self.approve_for_all(to, approved, u64::MAX)?;
+            Ok(())
+        }
+
+        /// Approves the operator for all tokens of the caller until the block
+        /// timestamp passes `deadline`.
+        #[ink(message)]
+        pub fn set_approval_for_all_until(
+            &mut self,
+            operator: AccountId,
+            deadline: u64,
+        ) -> Result<(), Error> {
+            self.approve_for_all(operator, true, deadline)?;
+            Ok(())
+        }
+
+        /// Returns the timestamp after which the operator approval expires, if approved.
+        #[ink(message)]
+        pub fn operator_expiry(&self, owner: AccountId, operator: AccountId) -> Option<u64> {
+            match self.operator_approvals.get(&(owner, operator)) {
+                Some((true, deadline)) => Some(*deadline),
+                _ => None,
+            }
+        }
+
+        /// Revokes the approval of token `id` without transferring it.
+        /// Only the token owner can do this.
+        #[ink(message)]
+        pub fn revoke_approval(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.owner_of(id).ok_or(Error::TokenNotFound)? != caller {
+                return Err(Error::NotOwner)
+            };
+            self.clear_approval(id)
+        }
+
+        /// Revokes the approval of every token owned by the caller.
+        /// Returns the number of approvals cleared.
+        #[ink(message)]
+        pub fn revoke_all_token_approvals(&mut self) -> Result<u32, Error> {
+            let caller = self.env().caller();
+            let mut cleared = 0;
+            for id in self.owned_tokens_from(caller, 0, self.balance_of_or_zero(&caller)) {
+                if self.has_approval(&caller, id) {
+                    self.clear_approval(id)?;
+                    cleared += 1;
+                }
+            }
+            Ok(cleared)
+        }
+
+        /// Returns the owner, approved account, metadata and URI of token `id` at once.
+        #[ink(message)]
+        pub fn token_info(&self, id: TokenId) -> TokenInfo {
+            TokenInfo {
+                owner: self.owner_of(id),
+                approved: self.get_approved(id),
+                metadata: self.metadata_of(id),
+                uri: self.token_uri(id),
+            }
+        }
+
+        /// Returns the metadata of the token if any.
+        #[ink(message)]
+        pub fn metadata_of(&self, id: TokenId) -> Option<u32> {
+            self.matedatas.get(&id).cloned()
+        }
+
+        /// Returns the existing token `metadata` was last set on, if any.
+        ///
+        /// Metadata values need not be unique; if several tokens share one, the
+        /// token it was written to last wins.
+        #[ink(message)]
+        pub fn token_by_metadata(&self, metadata: u32) -> Option<TokenId> {
+            self.metadata_to_token.get(&metadata).cloned()
+        }
+
+        /// Returns the URI of the token if any.
+        /// Falls back to the base URI followed by the token ID for existing tokens.
+        #[ink(message)]
+        pub fn token_uri(&self, id: TokenId) -> Option<String> {
+            if let Some(uri) = self.token_uris.get(&id) {
+                return Some(uri.clone())
+            };
+            if !self.exists(id) {
+                return None
+            };
+            self.base_uri.as_ref().map(|base| base.clone() + &id.to_string())
+        }
+
+        /// Returns the base URI of the collection if any.
+        #[ink(message)]
+        pub fn base_uri(&self) -> Option<String> {
+            self.base_uri.clone()
+        }
+
+        /// Sets the base URI of the collection. Only the contract owner can change it.
+        #[ink(message)]
+        pub fn set_base_uri(&mut self, base_uri: String) -> Result<(), Error> {
+            self.only_owner()?;
+            self.when_metadata_not_frozen()?;
+            self.base_uri = Some(normalize_uri(&base_uri)?);
+            Ok(())
+        }
+
+        /// Returns `true` if the metadata has been frozen.
+        #[ink(message)]
+        pub fn metadata_frozen(&self) -> bool {
+            self.metadata_frozen
+        }
+
+        /// Permanently freezes the base URI, every token URI and the token metadata.
+        /// Only the contract owner can do this.
+        #[ink(message)]
+        pub fn freeze_metadata(&mut self) -> Result<(), Error> {
+            self.only_owner()?;
+            self.metadata_frozen = true;
+            Ok(())
+        }
+
+        /// Returns `true` if the URI, metadata and attributes of token `id` can no longer be changed.
+        #[ink(message)]
+        pub fn token_metadata_frozen(&self, id: TokenId) -> bool {
+            self.metadata_frozen || self.token_metadata_frozen.contains_key(&id)
+        }
+
+        /// Permanently freezes the URI, metadata and attributes of token `id`.
+        /// Only the token owner can do this.
+        #[ink(message)]
+        pub fn freeze_token_metadata(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.owner_of(id).ok_or(Error::TokenNotFound)? != caller {
+                return Err(Error::NotOwner)
+            };
+            self.token_metadata_frozen.insert(id, true);
+            Ok(())
+        }
+
+        /// Approves the account to transfer the specified token on behalf of the caller.
+        /// In `ApprovalMode::Overwrite` the last user will be valid, in
+        /// `ApprovalMode::Multi` all of them are.
+        #[ink(message)]
+        pub fn approve(&mut self, to: AccountId, id: TokenId) -> Result<(), Error> {
+            self.approve_for(&to, id, u64::MAX)?;
+            Ok(())
+        }
+
+        /// Approves the account to transfer the specified token on behalf of the caller
+        /// until the block timestamp passes `deadline`. Not available in `ApprovalMode::Multi`.
+        #[ink(message)]
+        pub fn approve_until(
+            &mut self,
+            to: AccountId,
+            id: TokenId,
+            deadline: u64,
+        ) -> Result<(), Error> {
+            self.approve_for(&to, id, deadline)?;
+            Ok(())
+        }
+
+        /// Approves the account like `approve`, but only if `get_approved` still returns
+        /// `expected_current`, so an approval changed in the meantime is not overwritten.
+        #[ink(message)]
+        pub fn approve_if_current(
+            &mut self,
+            to: AccountId,
+            id: TokenId,
+            expected_current: Option<AccountId>,
+        ) -> Result<(), Error> {
+            if self.get_approved(id) != expected_current {
+                return Err(Error::ApprovalChanged)
+            };
+            self.approve_for(&to, id, u64::MAX)?;
+            Ok(())
+        }
+
+        /// Approves the account to transfer each of the specified tokens on behalf of the caller.
+        /// Either all tokens are approved or none.
+        #[ink(message)]
+        pub fn batch_approve(&mut self, to: AccountId, ids: Vec<TokenId>) -> Result<(), Error> {
+            self.when_approvals_not_paused()?;
+            let caller = self.env().caller();
+            if to == AccountId::from(ZERO_ADDRESS) || to == caller {
+                return Err(Error::NotAllowed)
+            };
+            for id in ids.iter() {
+                if self.owner_of(*id).ok_or(Error::TokenNotFound)? != caller {
+                    return Err(Error::NotAllowed)
+                };
+                self.ensure_not_soulbound(*id)?;
+                self.ensure_not_locked(*id)?;
+            }
+            for id in ids {
+                self.approve_for(&to, id, u64::MAX)?;
+            }
+            Ok(())
+        }
+
+        /// Transfers the token from the caller to the given destination.
+        ///
+        /// The transfer fee, if any, has to be paid along with the call.
+        #[ink(message, payable)]
+        pub fn transfer(
+            &mut self,
+            destination: AccountId,
+            id: TokenId,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.transfer_token_from(&caller, &destination, id,false)
+        }
+
+        /// Transfers the token from the caller to `to` like `transfer`, tagging the
+        /// transfer with `memo` of at most `MAX_MEMO_LEN` bytes, e.g. a deposit reference.
+        #[ink(message, payable)]
+        pub fn transfer_with_memo(
+            &mut self,
+            to: AccountId,
+            id: TokenId,
+            memo: Vec<u8>,
+        ) -> Result<(), Error> {
+            if memo.len() > MAX_MEMO_LEN {
+                return Err(Error::NotAllowed)
+            };
+            self.transfer(to, id)?;
+            self.env().emit_event(TransferMemo { id, memo });
+            Ok(())
+        }
+
+        /// Transfers the token from the caller to `to`, then calls
+        /// `on_token_transfer(from, id, data)` on `to` if it is a contract.
+        /// If that call fails the whole call is reverted with `Error::NotReceiver`,
+        /// undoing the transfer and anything `to` changed meanwhile.
+        ///
+        /// The transfer fee, if any, has to be paid along with the call.
+        #[ink(message, payable)]
+        pub fn transfer_and_call(
+            &mut self,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.transfer_token_from(&caller, &to, id, false)?;
+            let called =
+                self.call_out(|contract| contract.call_on_token_transfer(caller, to, id, data));
+            match called {
+                Ok(()) => Ok(()),
+                Err(error) if is_plain_account(&error) => Ok(()),
+                Err(_) => self.revert(Error::NotReceiver),
+            }
+        }
+
+        /// Moves token `id` from `from` to `to` regardless of approvals, locks and pauses,
+        /// e.g. to recover it from a lost account. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn admin_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> Result<(), Error> {
+            self.only_owner()?;
+            if self.reentrancy_locked {
+                return Err(Error::Reentrancy)
+            };
+            if to == AccountId::from(ZERO_ADDRESS) {
+                return Err(Error::NotAllowed)
+            };
+            if self.owner_of(id).ok_or(Error::TokenNotFound)? != from {
+                return Err(Error::NotOwner)
+            };
+            self.move_token(&from, &to, id)?;
+            self.env().emit_event(ForcedTransfer {
+                from,
+                to,
+                id,
+                by: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Sends token `id` of the foreign NFT contract `nft_contract`, which was sent
+        /// to this contract by mistake, to `to` by calling its `transfer(to, id)`.
+        /// Only the contract owner can do this.
+        #[ink(message)]
+        pub fn rescue_nft(
+            &mut self,
+            nft_contract: AccountId,
+            id: TokenId,
+            to: AccountId,
+        ) -> Result<(), Error> {
+            self.only_owner()?;
+            // Own tokens are never held by the contract itself.
+            if nft_contract == self.env().account_id() || to == AccountId::from(ZERO_ADDRESS) {
+                return Err(Error::NotAllowed)
+            };
+            match self.call_foreign_transfer(nft_contract, to, id) {
+                Ok(Ok(())) => Ok(()),
+                _ => Err(Error::RescueFailed),
+            }
+        }
+
+        /// Approves `to` for token `id` and immediately transfers the token to it.
+        /// Behaves like `transfer`, but also emits an `Approval` event for `to` first.
+        #[ink(message, payable)]
+        pub fn approve_and_transfer(&mut self, to: AccountId, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.ensure_can_transfer_to(&caller, &to, id, false)?;
+            self.collect_transfer_fee(1)?;
+            self.env().emit_event(Approval {
+                from: caller,
+                to,
+                id,
+            });
+            self.move_token(&caller, &to, id)
+        }
+
+        /// Swaps the caller's token `my_id` for token `their_id` of `counterparty`.
+        ///
+        /// The caller has to have approved this contract for `my_id` and has to be
+        /// approved for `their_id`. Either both tokens change hands or neither does.
+        /// The caller pays the transfer fee, if any, for both tokens.
+        #[ink(message, payable)]
+        pub fn atomic_swap(
+            &mut self,
+            my_id: TokenId,
+            their_id: TokenId,
+            counterparty: AccountId,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if my_id == their_id || counterparty == caller {
+                return Err(Error::NotAllowed)
+            };
+            self.ensure_can_transfer(my_id, false)?;
+            if !self.approved_for_token(my_id, self.env().account_id()) {
+                return Err(Error::NotApproved)
+            };
+            self.ensure_can_transfer(their_id, true)?;
+            if self.owner_of(their_id) != Some(counterparty) {
+                return Err(Error::NotOwner)
+            };
+            if self.is_denied(counterparty) {
+                return Err(Error::Denied)
+            };
+            self.collect_transfer_fee(2)?;
+            self.move_token(&caller, &counterparty, my_id)?;
+            self.move_token(&counterparty, &caller, their_id)
+        }
+
+        /// Transfers all tokens `ids` from the caller to the given destination.
+        ///
+        /// Either all tokens are transferred or, if any of them cannot be, none is.
+        /// The transfer fee, if any, has to be paid along with the call for every token.
+        #[ink(message, payable)]
+        pub fn batch_transfer(
+            &mut self,
+            destination: AccountId,
+            ids: Vec<TokenId>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            for (i, id) in ids.iter().enumerate() {
+                self.ensure_can_transfer_to(&caller, &destination, *id, false)?;
+                if ids[..i].contains(id) {
+                    return Err(Error::NotAllowed)
+                };
+            }
+            self.collect_transfer_fee(ids.len() as u32)?;
+            for id in ids {
+                self.move_token(&caller, &destination, id)?;
+            }
+            Ok(())
+        }
+
+        /// Transfer approved or owned token.
+        ///
+        /// The transfer fee, if any, has to be paid along with the call.
+        #[ink(message, payable)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> Result<(), Error> {
+            self.transfer_token_from(&from, &to, id,true)
+        }
+
+        /// Transfer approved token, making sure a contract destination can handle it.
+        ///
+        /// If `to` is a contract its `on_erc721_received` message is called after
+        /// the token is moved and has to return `ON_ERC721_RECEIVED`, or the whole
+        /// call is reverted with `Error::NotReceiver`, undoing the transfer and
+        /// anything `to` changed meanwhile. Otherwise it behaves like `transfer_from`.
+        ///
+        /// The transfer fee, if any, has to be paid along with the call.
+        #[ink(message, payable)]
+        pub fn safe_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.ensure_can_transfer(id, true)?;
+            // Do not tell the receiver about a sender that does not own the token.
+            if self.owner_of(id) != Some(from) {
+                return Err(Error::NotOwner)
+            };
+            self.transfer_token_from(&from, &to, id,true)?;
+            let accepted =
+                self.call_out(|contract| contract.ensure_receiver_accepts(caller, from, to, id, data));
+            if let Err(error) = accepted {
+                self.revert(error)
+            };
+            Ok(())
+        }
+
+        /// Sets the URI of the token. Only the token owner can change it.
+        #[ink(message)]
+        pub fn set_token_uri(&mut self, id: TokenId, uri: String) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.when_metadata_not_frozen()?;
+            if !self.exists(id) {
+                return Err(Error::TokenNotFound)
+            };
+            if self.owner_of(id) != Some(caller) {
+                return Err(Error::NotOwner)
+            };
+            self.when_token_metadata_not_frozen(id)?;
+            let uri = normalize_uri(&uri)?;
+            self.token_uris.insert(id, uri);
+            self.env().emit_event(MetadataUpdate { id });
+            Ok(())
+        }
+
+        /// Sets the URIs of the consecutive tokens starting at `from_id`.
+        /// Only the token owner can change them; either all URIs are set or none.
+        #[ink(message)]
+        pub fn set_token_uris(&mut self, from_id: TokenId, uris: Vec<String>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.when_metadata_not_frozen()?;
+            let to_id = (uris.len() as TokenId)
+                .checked_sub(1)
+                .and_then(|last| from_id.checked_add(last))
+                .ok_or(Error::NotAllowed)?;
+            for id in from_id..=to_id {
+                if !self.exists(id) {
+                    return Err(Error::TokenNotFound)
+                };
+                if self.owner_of(id) != Some(caller) {
+                    return Err(Error::NotOwner)
+                };
+                self.when_token_metadata_not_frozen(id)?;
+            }
+            let uris = uris
+                .iter()
+                .map(|uri| normalize_uri(uri))
+                .collect::<Result<Vec<_>, _>>()?;
+            for (id, uri) in (from_id..=to_id).zip(uris) {
+                self.token_uris.insert(id, uri);
+            }
+            self.env().emit_event(BatchMetadataUpdate { from_id, to_id });
+            Ok(())
+        }
+
+        /// Sets the metadata of the token. Only the token owner can change it.
+        #[ink(message)]
+        pub fn set_metadata(&mut self, id: TokenId, value: u32) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.when_metadata_not_frozen()?;
+            if !self.exists(id) {
+                return Err(Error::TokenNotFound)
+            };
+            if self.owner_of(id) != Some(caller) {
+                return Err(Error::NotOwner)
+            };
+            self.when_token_metadata_not_frozen(id)?;
+            self.insert_metadata(id, value);
+            self.env().emit_event(MetadataUpdate { id });
+            Ok(())
+        }
+
+        /// Sets the metadata of token `id` whoever owns it. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn admin_set_metadata(&mut self, id: TokenId, value: u32) -> Result<(), Error> {
+            self.only_owner()?;
+            self.when_metadata_not_frozen()?;
+            if !self.exists(id) {
+                return Err(Error::TokenNotFound)
+            };
+            self.when_token_metadata_not_frozen(id)?;
+            self.insert_metadata(id, value);
+            self.env().emit_event(MetadataUpdate { id });
+            Ok(())
+        }
+
+        /// Returns the value of attribute `key` of token `id`, if set.
+        #[ink(message)]
+        pub fn get_attribute(&self, id: TokenId, key: String) -> Option<String> {
+            self.attributes.get(&(id, key)).cloned()
+        }
+
+        /// Returns up to `limit`, but at most `MAX_PAGE`, `(key, value)` attributes of
+        /// token `id` ordered by key, skipping the first `start`.
+        #[ink(message)]
+        pub fn attributes_of(&self, id: TokenId, start: u32, limit: u32) -> Vec<(String, String)> {
+            self.attribute_keys
+                .get(&id)
+                .into_iter()
+                .flatten()
+                .skip(start as usize)
+                .take(limit.min(MAX_PAGE) as usize)
+                .map(|key| (key.clone(), self.attributes[&(id, key.clone())].clone()))
+                .collect()
+        }
+
+        /// Returns the metadata of token `id` as JSON, with its name, its URI as image
+        /// and its on-chain attributes, or `None` if it does not exist.
+        #[ink(message)]
+        pub fn token_metadata_json(&self, id: TokenId) -> Option<String> {
+            if !self.exists(id) {
+                return None
+            };
+            let mut name = self.name.clone().map(|name| name + " ").unwrap_or_default();
+            name.push('#');
+            name.push_str(&id.to_string());
+            let mut json = String::from("{\"name\":");
+            push_json_string(&mut json, &name);
+            json.push_str(",\"image\":");
+            match self.token_uri(id) {
+                Some(uri) => push_json_string(&mut json, &uri),
+                None => json.push_str("null"),
+            }
+            json.push_str(",\"attributes\":[");
+            for (i, (key, value)) in self.attributes_of(id, 0, MAX_ATTRIBUTES as u32).iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                json.push_str("{\"trait_type\":");
+                push_json_string(&mut json, key);
+                json.push_str(",\"value\":");
+                push_json_string(&mut json, value);
+                json.push('}');
+            }
+            json.push_str("]}");
+            Some(json)
+        }
+
+        /// Sets attribute `key` of token `id` to `value`, e.g. `("rarity", "legendary")`.
+        /// Only the token owner can do this.
+        ///
+        /// Keys and values are at most `MAX_ATTRIBUTE_LEN` bytes long, and a token has
+        /// at most `MAX_ATTRIBUTES` attributes.
+        #[ink(message)]
+        pub fn set_attribute(&mut self, id: TokenId, key: String, value: String) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.when_metadata_not_frozen()?;
+            if self.owner_of(id).ok_or(Error::TokenNotFound)? != caller {
+                return Err(Error::NotOwner)
+            };
+            self.when_token_metadata_not_frozen(id)?;
+            if key.len() > MAX_ATTRIBUTE_LEN || value.len() > MAX_ATTRIBUTE_LEN {
+                return Err(Error::NotAllowed)
+            };
+            let mut keys = self.attribute_keys.get(&id).cloned().unwrap_or_default();
+            if let Err(index) = keys.binary_search(&key) {
+                if keys.len() >= MAX_ATTRIBUTES {
+                    return Err(Error::NotAllowed)
+                };
+                keys.insert(index, key.clone());
+                self.attribute_keys.insert(id, keys);
+            }
+            self.attributes.insert((id, key), value);
+            self.env().emit_event(MetadataUpdate { id });
+            Ok(())
+        }
+
+        /// Returns the first and the last block in which minting is possible, if limited.
+        #[ink(message)]
+        pub fn mint_window(&self) -> (Option<BlockNumber>, Option<BlockNumber>) {
+            (self.mint_start_block, self.mint_end_block)
+        }
+
+        /// Restricts minting to the blocks `start..=end`. `None` leaves that side open.
+        /// Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_mint_window(
+            &mut self,
+            start: Option<BlockNumber>,
+            end: Option<BlockNumber>,
+        ) -> Result<(), Error> {
+            self.only_owner()?;
+            if let (Some(start), Some(end)) = (start, end) {
+                if start > end {
+                    return Err(Error::NotAllowed)
+                };
+            }
+            self.mint_start_block = start;
+            self.mint_end_block = end;
+            Ok(())
+        }
+
+        /// Returns the number of tokens `who` may still mint through `allowlist_mint`.
+        #[ink(message)]
+        pub fn allowlist_quota(&self, who: AccountId) -> u32 {
+            self.allowlist.get(&who).cloned().unwrap_or(0)
+        }
+
+        /// Allows `who` to mint `quota` tokens through `allowlist_mint`, replacing its
+        /// remaining quota. Only the contract owner can do this.
+        #[ink(message)]
+        pub fn set_allowlist(&mut self, who: AccountId, quota: u32) -> Result<(), Error> {
+            self.only_owner()?;
+            self.set_allowlist_quota(who, quota);
+            Ok(())
+        }
+
+        /// Creates a new token owned by the caller, using up one of its allowlist quota.
+        #[ink(message)]
+        pub fn allowlist_mint(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let quota = self.allowlist_quota(caller);
+            if quota == 0 {
+                return Err(Error::NotAllowed)
+            };
+            self.when_mints_not_paused()?;
+            self.when_mint_open()?;
+            self.mint_token(&caller, id)?;
+            self.set_allowlist_quota(caller, quota - 1);
+            Ok(())
+        }
+
+        /// Creates a new token owned by the caller. Only the contract owner and minters can mint.
+        #[ink(message)]
+        pub fn mint(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.mint_to(caller, id)
+        }
+
+        /// Creates a new token owned by the caller that can never be transferred or approved,
+        /// only burned. Only the contract owner and minters can mint.
+        #[ink(message)]
+        pub fn mint_soulbound(&mut self, id: TokenId) -> Result<(), Error> {
+            self.mint(id)?;
+            self.soulbound.insert(id, true);
+            Ok(())
+        }
+
+        /// Returns `true` if token `id` can never be transferred.
+        #[ink(message)]
+        pub fn is_soulbound(&self, id: TokenId) -> bool {
+            self.soulbound.contains_key(&id)
+        }
+
+        /// Creates a new token owned by the caller that represents `quantity` copies.
+        /// The copies are always transferred together. Only the contract owner and minters can mint.
+        #[ink(message)]
+        pub fn mint_with_quantity(&mut self, id: TokenId, quantity: u32) -> Result<(), Error> {
+            self.only_minter()?;
+            if self.exists(id) {
+                return Err(Error::TokenExists)
+            };
+            if quantity == 0 {
+                return Err(Error::NotAllowed)
+            };
+            self.mint(id)?;
+            self.quantity.insert(id, quantity);
+            Ok(())
+        }
+
+        /// Creates a new token owned by the caller and sets its URI.
+        /// Only the contract owner and minters can mint.
+        #[ink(message)]
+        pub fn mint_with_uri(&mut self, id: TokenId, uri: String) -> Result<(), Error> {
+            self.when_metadata_not_frozen()?;
+            let uri = normalize_uri(&uri)?;
+            self.mint(id)?;
+            self.token_uris.insert(id, uri);
+            self.env().emit_event(MetadataUpdate { id });
+            Ok(())
+        }
+
+        /// Returns the number of copies token `id` represents, `0` if it does not exist.
+        #[ink(message)]
+        pub fn quantity_of(&self, id: TokenId) -> u32 {
+            if !self.exists(id) {
+                return 0
+            };
+            self.quantity.get(&id).cloned().unwrap_or(1)
+        }
+
+        /// Returns the account that minted token `id`, whoever owns it now.
+        #[ink(message)]
+        pub fn minter_of(&self, id: TokenId) -> Option<AccountId> {
+            self.original_minter.get(&id).cloned()
+        }
+
+        /// Creates a token with the next free sequential ID, owned by the caller.
+        /// Only the contract owner and minters can mint. Returns the ID used.
+        #[ink(message)]
+        pub fn mint_next(&mut self) -> Result<TokenId, Error> {
+            let caller = self.env().caller();
+            let mut id = self.next_token_id;
+            while self.exists(id) {
+                id = id.checked_add(1).ok_or(Error::MaxSupplyReached)?;
+            }
+            self.mint_to(caller, id)?;
+            self.next_token_id = id.saturating_add(1);
+            Ok(id)
+        }
+
+        /// Creates a new token owned by `to`. Only the contract owner and minters can mint.
+        #[ink(message)]
+        pub fn mint_to(&mut self, to: AccountId, id: TokenId) -> Result<(), Error> {
+            self.only_minter()?;
+            self.when_mints_not_paused()?;
+            self.when_mint_open()?;
+            self.mint_token(&to, id)?;
+            Ok(())
+        }
+
+        /// Creates all tokens `ids` owned by the caller.
+        /// Only the contract owner and minters can mint.
+        ///
+        /// Either all tokens are created or, if any of them already exists, none is.
+        #[ink(message)]
+        pub fn batch_mint(&mut self, ids: Vec<TokenId>) -> Result<(), Error> {
+            self.only_minter()?;
+            self.when_mints_not_paused()?;
+            self.when_mint_open()?;
+            let caller = self.env().caller();
+            for (i, id) in ids.iter().enumerate() {
+                if self.exists(*id) || ids[..i].contains(id) {
+                    return Err(Error::TokenExists)
+                };
+            }
+            self.ensure_supply_for(ids.len() as u32)?;
+            for id in ids {
+                self.mint_token(&caller, id)?;
+            }
+            Ok(())
+        }
+
+        /// Creates the `count` tokens `start..start + count` owned by the caller.
+        /// Only the contract owner and minters can mint.
+        ///
+        /// Either all tokens are created or, if any of them already exists, none is.
+        #[ink(message)]
+        pub fn mint_range(&mut self, start: TokenId, count: u32) -> Result<(), Error> {
+            self.only_minter()?;
+            self.when_mints_not_paused()?;
+            self.when_mint_open()?;
+            let caller = self.env().caller();
+            let end = start.checked_add(count).ok_or(Error::NotAllowed)?;
+            if (start..end).any(|id| self.exists(id)) {
+                return Err(Error::TokenExists)
+            };
+            self.ensure_supply_for(count)?;
+            for id in start..end {
+                self.mint_token(&caller, id)?;
+            }
+            Ok(())
+        }
+
+        /// Deletes an existing token. Only the owner can burn the token.
+        #[ink(message)]
+        pub fn burn(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.exists(id) {
+                return Err(Error::TokenNotFound)
+            };
+            if self.owner_of(id) != Some(caller) {
+                return Err(Error::NotOwner)
+            };
+            self.ensure_not_locked(id)?;
+            self.burn_token(&caller, id)
+        }
+
+        /// Deletes an existing token of `from`. The caller must own the token,
+        /// be approved for it or be an approved operator of its owner.
+        #[ink(message)]
+        pub fn burn_from(&mut self, from: AccountId, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.exists(id) {
+                return Err(Error::TokenNotFound)
+            };
+            if self.owner_of(id) != Some(from) {
+                return Err(Error::NotOwner)
+            };
+            if !self.is_owner_or_approved(caller, id) {
+                return Err(Error::NotApproved)
+            };
+            if caller != from {
+                self.ensure_not_soulbound(id)?;
+            }
+            self.ensure_not_locked(id)?;
+            self.burn_token(&from, id)
+        }
+
+        /// Returns the account token `id` is locked to if any.
+        #[ink(message)]
+        pub fn locker_of(&self, id: TokenId) -> Option<AccountId> {
+            self.locked.get(&id).cloned()
+        }
+
+        /// Locks token `id` to `locker`, e.g. a fractionalization vault.
+        /// Only the locker can then transfer the token, without needing an approval,
+        /// and unlock it. Nobody can approve the token while it is locked.
+        #[ink(message)]
+        pub fn lock(&mut self, id: TokenId, locker: AccountId) -> Result<(), Error> {
+            self.when_transfers_not_paused()?;
+            let caller = self.env().caller();
+            if self.owner_of(id).ok_or(Error::TokenNotFound)? != caller {
+                return Err(Error::NotOwner)
+            };
+            if self.locked.contains_key(&id) {
+                return Err(Error::TokenLocked)
+            };
+            if locker == AccountId::from(ZERO_ADDRESS) {
+                return Err(Error::NotAllowed)
+            };
+            self.locked.insert(id, locker);
+            Ok(())
+        }
+
+        /// Returns the timestamp until which token `id` cannot be transferred, if in the future.
+        #[ink(message)]
+        pub fn locked_until(&self, id: TokenId) -> Option<u64> {
+            self.timelocks
+                .get(&id)
+                .filter(|timestamp| self.env().block_timestamp() < **timestamp)
+                .cloned()
+        }
+
+        /// Prevents token `id` from being transferred until `timestamp`.
+        /// Only the token owner can do this, and an existing lock can only be extended.
+        #[ink(message)]
+        pub fn lock_until(&mut self, id: TokenId, timestamp: u64) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.owner_of(id).ok_or(Error::TokenNotFound)? != caller {
+                return Err(Error::NotOwner)
+            };
+            if self.locked_until(id).is_some_and(|until| timestamp < until) {
+                return Err(Error::TokenLocked)
+            };
+            self.timelocks.insert(id, timestamp);
+            Ok(())
+        }
+
+        /// Unlocks token `id`. Only its locker can do this.
+        #[ink(message)]
+        pub fn unlock(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            match self.locked.get(&id) {
+                None => Err(Error::NotAllowed),
+                Some(locker) if *locker != caller => Err(Error::TokenLocked),
+                Some(_) => {
+                    self.locked.take(&id);
+                    Ok(())
+                }
+            }
+        }
+
+        /// Returns the vault token `id` is wrapped in, if any.
+        #[ink(message)]
+        pub fn wrapped_in(&self, id: TokenId) -> Option<AccountId> {
+            self.wrapped_in.get(&id).cloned()
+        }
+
+        /// Transfers token `id` from the caller into `vault`, e.g. a fractionalization
+        /// or lending vault. The vault owns the token but cannot move it until it
+        /// calls `unwrap`, which returns the token to the caller.
+        ///
+        /// The transfer fee, if any, has to be paid along with the call.
+        #[ink(message, payable)]
+        pub fn wrap(&mut self, id: TokenId, vault: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if vault == AccountId::from(ZERO_ADDRESS) || vault == caller {
+                return Err(Error::NotAllowed)
+            };
+            self.transfer_token_from(&caller, &vault, id, false)?;
+            self.wrapped_in.insert(id, vault);
+            self.wrapped_by.insert(id, caller);
+            Ok(())
+        }
+
+        /// Returns wrapped token `id` to the account that wrapped it.
+        /// Only the vault it is wrapped in can do this.
+        #[ink(message)]
+        pub fn unwrap(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.reentrancy_locked {
+                return Err(Error::Reentrancy)
+            };
+            if self.wrapped_in(id).ok_or(Error::NotAllowed)? != caller {
+                return Err(Error::NotOwner)
+            };
+            let depositor = *self.wrapped_by.get(&id).ok_or(Error::CannotFetchValue)?;
+            self.move_token(&caller, &depositor, id)
+        }
+
+        /// Returns the price token `id` is listed for, if any.
+        #[ink(message)]
+        pub fn price_of(&self, id: TokenId) -> Option<Balance> {
+            self.listings.get(&id).cloned()
+        }
+
+        /// Lists token `id` for sale at `price`. Only the token owner can do this.
+        #[ink(message)]
+        pub fn list_for_sale(&mut self, id: TokenId, price: Balance) -> Result<(), Error> {
+            self.when_transfers_not_paused()?;
+            let caller = self.env().caller();
+            if self.owner_of(id).ok_or(Error::TokenNotFound)? != caller {
+                return Err(Error::NotOwner)
+            };
+            self.ensure_not_soulbound(id)?;
+            self.listings.insert(id, price);
+            Ok(())
+        }
+
+        /// Withdraws token `id` from sale. Only the token owner can do this.
+        #[ink(message)]
+        pub fn cancel_listing(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.owner_of(id).ok_or(Error::TokenNotFound)? != caller {
+                return Err(Error::NotOwner)
+            };
+            self.listings.take(&id).ok_or(Error::NotForSale)?;
+            Ok(())
+        }
+
+        /// Buys listed token `id`. The royalty due on the listed price is paid to its
+        /// receiver, the rest to the seller, and the transfer fee, if any, has to be paid
+        /// on top of the price. Anything paid beyond both is refunded.
+        #[ink(message, payable)]
+        pub fn buy(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let price = *self.listings.get(&id).ok_or(Error::NotForSale)?;
+            self.ensure_movable(id)?;
+            let seller = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if caller == seller {
+                return Err(Error::NotAllowed)
+            };
+            if self.is_denied(caller) {
+                return Err(Error::Denied)
+            };
+            let paid = self.env().transferred_balance();
+            let due = price.saturating_add(self.transfer_fee);
+            if paid < due {
+                return Err(Error::InsufficientPayment)
+            };
+            let (receiver, royalty) = self.royalty_info(id, price);
+            let royalty = if receiver == AccountId::from(ZERO_ADDRESS) { 0 } else { royalty };
+            if royalty > 0 {
+                self.env()
+                    .transfer(receiver, royalty)
+                    .map_err(|_| Error::PaymentFailed)?;
+            }
+            self.env()
+                .transfer(seller, price - royalty)
+                .map_err(|_| Error::PaymentFailed)?;
+            if self.transfer_fee > 0 {
+                self.env()
+                    .transfer(self.treasury, self.transfer_fee)
+                    .map_err(|_| Error::PaymentFailed)?;
+            }
+            if paid > due {
+                self.env()
+                    .transfer(caller, paid - due)
+                    .map_err(|_| Error::PaymentFailed)?;
+            }
+            self.move_token(&seller, &caller, id)?;
+            self.env().emit_event(Sold {
+                id,
+                from: seller,
+                to: caller,
+                price,
+            });
+            Ok(())
+        }
+
+        /// Forwards the transfer fee of `count` tokens to the treasury and refunds anything
+        /// paid on top of it. Fails with `Error::InsufficientPayment` if less was paid.
+        ///
+        /// Errors do not roll back state, so this has to run before any token moves.
+        fn collect_transfer_fee(&mut self, count: u32) -> Result<(), Error> {
+            let fee = self.transfer_fee.saturating_mul(count.into());
+            if fee == 0 {
+                return Ok(())
+            };
+            let paid = self.env().transferred_balance();
+            if paid < fee {
+                return Err(Error::InsufficientPayment)
+            };
+            self.env()
+                .transfer(self.treasury, fee)
+                .map_err(|_| Error::PaymentFailed)?;
+            if paid > fee {
+                self.env()
+                    .transfer(self.env().caller(), paid - fee)
+                    .map_err(|_| Error::PaymentFailed)?;
+            }
+            Ok(())
+        }
+
+        /// Transfers token `id` `from` the sender to the `to` AccountId, collecting
+        /// the transfer fee first.
+        fn transfer_token_from(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            id: TokenId,
+            need_approval: bool,
+        ) -> Result<(), Error> {
+            self.ensure_can_transfer_to(from, to, id, need_approval)?;
+            self.collect_transfer_fee(1)?;
+            self.move_token(from, to, id)
+        }
+
+        /// Checks that the caller is allowed to move token `id` from `from` to `to`.
+        fn ensure_can_transfer_to(
+            &self,
+            from: &AccountId,
+            to: &AccountId,
+            id: TokenId,
+            need_approval: bool,
+        ) -> Result<(), Error> {
+            if *to == AccountId::from(ZERO_ADDRESS) {
+                return Err(Error::NotAllowed)
+            };
+            self.ensure_can_transfer(id, need_approval)?;
+            // Moving the token out of the wrong account would corrupt its indexes.
+            if self.owner_of(id) != Some(*from) {
+                return Err(Error::NotOwner)
+            };
+            if self.is_denied(*to) {
+                return Err(Error::Denied)
+            };
+            Ok(())
+        }
+
+        /// Moves token `id` from `from` to `to`, dropping everything tied to the previous owner.
+        fn move_token(&mut self, from: &AccountId, to: &AccountId, id: TokenId) -> Result<(), Error> {
+            self.on_owner_change(from, Some(to), id)?;
+            self.remove_token_from(from, id)?;
+            self.add_token_to(to, id)?;
+            self.emit_transfer(Some(*from), Some(*to), id);
+            Ok(())
+        }
+
+        /// Drops the state tied to `from` owning token `id` before it moves to `to`,
+        /// or before it is burned if `to` is `None`. Every owner-scoped map must be
+        /// cleaned up here, so neither a transfer nor a burn leaves it behind for the
+        /// next owner.
+        fn on_owner_change(
+            &mut self,
+            from: &AccountId,
+            to: Option<&AccountId>,
+            id: TokenId,
+        ) -> Result<(), Error> {
+            // Fail before anything is changed rather than in `add_token_to`.
+            if to.is_some_and(|to| self.is_denied(*to)) {
+                return Err(Error::Denied)
+            };
+            // Clear while the approval is still keyed by the previous owner.
+            if self.owner_of(id) == Some(*from) {
+                self.clear_approval(id)?;
+            };
+            self.locked.take(&id);
+            self.timelocks.take(&id);
+            self.listings.take(&id);
+            self.wrapped_in.take(&id);
+            self.wrapped_by.take(&id);
+            Ok(())
+        }
+
+        /// Fails with `Error::Soulbound` if token `id` can never be transferred.
+        fn ensure_not_soulbound(&self, id: TokenId) -> Result<(), Error> {
+            if self.soulbound.contains_key(&id) {
+                return Err(Error::Soulbound)
+            };
+            Ok(())
+        }
+
+        /// Fails with `Error::TokenLocked` if token `id` is locked to an account other than
+        /// the caller or wrapped in a vault.
+        fn ensure_not_locked(&self, id: TokenId) -> Result<(), Error> {
+            if self.wrapped_in.contains_key(&id) {
+                return Err(Error::TokenLocked)
+            };
+            match self.locked.get(&id) {
+                Some(locker) if *locker != self.env().caller() => Err(Error::TokenLocked),
+                _ => Ok(()),
+            }
+        }
+
+        /// Checks that token `id` can currently be moved at all.
+        fn ensure_movable(&self, id: TokenId) -> Result<(), Error> {
+            self.when_transfers_not_paused()?;
+            if self.reentrancy_locked {
+                return Err(Error::Reentrancy)
+            };
+            if !self.exists(id) {
+                return Err(Error::TokenNotFound)
+            };
+            self.ensure_not_soulbound(id)?;
+            self.ensure_not_locked(id)?;
+            if self.locked_until(id).is_some() {
+                return Err(Error::TokenLocked)
+            };
+            Ok(())
+        }
+
+        /// Checks that the caller is allowed to move token `id`.
+        fn ensure_can_transfer(&self, id: TokenId, need_approval: bool) -> Result<(), Error> {
+            self.ensure_movable(id)?;
+            let caller = self.env().caller();
+            if self.is_denied(caller) {
+                return Err(Error::Denied)
+            };
+            if !need_approval {
+                let owner = self.owner_of(id);
+                if !(owner == Some(caller)) {
+                    return Err(Error::NotAllowed)
+                }
+            }
+            if need_approval
+                && self.locked.get(&id) != Some(&caller)
+                && !self.approved_for_token(id,caller)
+                && !self.approved_for_operator(id, caller)
+            {
+                return Err(Error::NotApproved)
+            };
+            if need_approval && self.enforce_royalties && !self.is_whitelisted_marketplace(caller) {
+                return Err(Error::NotAllowed)
+            };
+            Ok(())
+        }
+
+        /// Asks `to` whether it accepts token `id` through its `on_erc721_received` message.
+        ///
+        /// Accounts that cannot be called (i.e. are not contracts) accept every token.
+        fn ensure_receiver_accepts(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            match self.call_on_erc721_received(operator, from, to, id, data) {
+                Ok(magic) if magic == ON_ERC721_RECEIVED => Ok(()),
+                Err(error) if is_plain_account(&error) => Ok(()),
+                _ => Err(Error::NotReceiver),
+            }
+        }
+
+        /// Runs `call` into another contract with the reentrancy lock held.
+        ///
+        /// The contract is only written to storage when a message returns, so it is
+        /// written before the call as well: messages called back by the other contract
+        /// load it from there and would otherwise see neither the lock nor the moved
+        /// token. It is loaded again afterwards to keep what those messages changed.
+        fn call_out<R>(&mut self, call: impl FnOnce(&mut Self) -> R) -> R {
+            let root = ink_primitives::Key::from([0x00; 32]);
+            self.reentrancy_locked = true;
+            ink_storage::traits::push_spread_root(self, &root);
+            let result = call(self);
+            let stored = ink_storage::traits::pull_spread_root(&root);
+            // Dropping the outdated contract would clear the storage cells it was loaded from.
+            core::mem::forget(core::mem::replace(self, stored));
+            self.reentrancy_locked = false;
+            result
+        }
+
+        /// Ends the call with `error`, discarding every change it made to storage,
+        /// including those of the contracts it called, and the value sent along.
+        ///
+        /// Returning `Err` from a message keeps its changes, so this is the only way
+        /// to undo a transfer a receiver refused, together with what the receiver
+        /// did meanwhile.
+        #[cfg(not(test))]
+        fn revert(&self, error: Error) -> ! {
+            ink_env::return_value::<Result<(), Error>>(
+                ink_env::ReturnFlags::default().set_reverted(true),
+                &Err(error),
+            )
+        }
+
+        /// Panics with `error`, as the off-chain environment cannot revert a call;
+        /// see `tests::reverted`.
+        #[cfg(test)]
+        fn revert(&self, error: Error) -> ! {
+            std::panic::panic_any(error)
+        }
+
+        /// Calls `on_erc721_received(operator, from, id, data)` on the `to` contract.
+        #[cfg(not(test))]
+        fn call_on_erc721_received(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> ink_env::Result<[u8; 4]> {
+            use ink_env::call::{
+                build_call,
+                utils::ReturnType,
+                ExecutionInput,
+                Selector,
+            };
+            build_call::<Environment>()
+                .callee(to)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_ERC721_RECEIVED))
+                        .push_arg(operator)
+                        .push_arg(from)
+                        .push_arg(id)
+                        .push_arg(data),
+                )
+                .returns::<ReturnType<[u8; 4]>>()
+                .fire()
+        }
+
+        /// The off-chain environment cannot call other contracts, so tests
+        /// dispatch to the mock receivers registered in `tests::receivers`.
+        #[cfg(test)]
+        fn call_on_erc721_received(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> ink_env::Result<[u8; 4]> {
+            tests::receivers::on_erc721_received(operator, from, to, id, data)
+        }
+
+        /// Removes token `id` of `from` from every collection.
+        fn burn_token(&mut self, from: &AccountId, id: TokenId) -> Result<(), Error> {
+            if self.reentrancy_locked {
+                return Err(Error::Reentrancy)
+            };
+            self.on_owner_change(from, None, id)?;
+            self.remove_token_from(from, id)?;
+            self.remove_token_from_all_tokens(id)?;
+            self.token_uris.take(&id);
+            self.soulbound.take(&id);
+            self.token_royalty.take(&id);
+            self.quantity.take(&id);
+            self.original_minter.take(&id);
+            self.token_metadata_frozen.take(&id);
+            for key in self.attribute_keys.take(&id).unwrap_or_default() {
+                self.attributes.take(&(id, key));
+            }
+            self.remove_metadata(id);
+            self.emit_transfer(Some(*from), None, id);
+            Ok(())
+        }
+
+        /// Emits a `Transfer` event and counts it.
+        fn emit_transfer(&mut self, from: Option<AccountId>, to: Option<AccountId>, id: TokenId) {
+            self.transfer_count += 1;
+            self.env().emit_event(Transfer { from, to, id });
+        }
+
+        /// Calls `on_token_transfer(from, id, data)` on the `to` contract.
+        #[cfg(not(test))]
+        fn call_on_token_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> ink_env::Result<()> {
+            use ink_env::call::{
+                build_call,
+                utils::ReturnType,
+                ExecutionInput,
+                Selector,
+            };
+            use crate::ON_TOKEN_TRANSFER;
+            build_call::<Environment>()
+                .callee(to)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_TOKEN_TRANSFER))
+                        .push_arg(from)
+                        .push_arg(id)
+                        .push_arg(data),
+                )
+                .returns::<ReturnType<()>>()
+                .fire()
+        }
+
+        /// Tests dispatch to the mock hooks registered in `tests::receivers`.
+        #[cfg(test)]
+        fn call_on_token_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> ink_env::Result<()> {
+            tests::receivers::on_token_transfer(from, to, id, data)
+        }
+
+        /// Calls `transfer(to, id)` on the `nft_contract` contract.
+        #[cfg(not(test))]
+        fn call_foreign_transfer(
+            &mut self,
+            nft_contract: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> ink_env::Result<Result<(), Error>> {
+            use ink_env::call::{
+                build_call,
+                utils::ReturnType,
+                ExecutionInput,
+                Selector,
+            };
+            use crate::TRANSFER_SELECTOR;
+            build_call::<Environment>()
+                .callee(nft_contract)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(TRANSFER_SELECTOR))
+                        .push_arg(to)
+                        .push_arg(id),
+                )
+                .returns::<ReturnType<Result<(), Error>>>()
+                .fire()
+        }
+
+        /// Calls the mock NFT contract registered for `nft_contract`, as the off-chain
+        /// environment cannot call other contracts.
+        #[cfg(test)]
+        fn call_foreign_transfer(
+            &mut self,
+            nft_contract: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> ink_env::Result<Result<(), Error>> {
+            tests::foreign::transfer(nft_contract, to, id)
+        }
+
+        /// Removes token `id` from the owner.
+        fn remove_token_from(
+            &mut self,
+            from: &AccountId,
+            id: TokenId,
+        ) -> Result<(), Error> {
+            let Self {
+                token_owner,
+                owned_tokens_count,
+                owned_tokens,
+                owned_tokens_index,
+                ..
+            } = self;
+            let occupied = match token_owner.entry(id) {
+                Entry::Vacant(_) => return Err(Error::TokenNotFound),
+                Entry::Occupied(occupied) => occupied,
+            };
+            decrease_counter_of(owned_tokens_count, from)?;
+            occupied.remove_entry();
+            // Move the owner's last token into the freed index.
+            let last_index = *owned_tokens_count.get(from).ok_or(Error::CannotFetchValue)?;
+            let index = owned_tokens_index.take(&id).ok_or(Error::CannotFetchValue)?;
+            let last = owned_tokens
+                .take(&(*from, last_index))
+                .ok_or(Error::CannotFetchValue)?;
+            if index != last_index {
+                owned_tokens.insert((*from, index), last);
+                owned_tokens_index.insert(last, index);
+            }
+            Ok(())
+        }
+
+        /// Adds the token `id` to the `to` AccountID.
+        fn add_token_to(&mut self, to: &AccountId, id: TokenId) -> Result<(), Error> {
+            if self.is_denied(*to) {
+                return Err(Error::Denied)
+            };
+            let Self {
+                token_owner,
+                owned_tokens_count,
+                owned_tokens,
+                owned_tokens_index,
+                ..
+            } = self;
+            let vacant_token_owner = match token_owner.entry(id) {
+                Entry::Vacant(vacant) => vacant,
+                Entry::Occupied(_) => return Err(Error::TokenExists),
+            };
+            if *to == AccountId::from(ZERO_ADDRESS) {
+                return Err(Error::NotAllowed)
+            };
+            let index = *owned_tokens_count.get(to).unwrap_or(&0);
+            let entry = owned_tokens_count.entry(*to);
+            increase_counter_of(entry)?;
+            vacant_token_owner.insert(*to);
+            owned_tokens.insert((*to, index), id);
+            owned_tokens_index.insert(id, index);
+            Ok(())
+        }
+
+        /// Creates the token `id` owned by `to` and makes it enumerable.
+        fn mint_token(&mut self, to: &AccountId, id: TokenId) -> Result<(), Error> {
+            // An existing token is reported as such even if the cap is reached.
+            if self.exists(id) {
+                return Err(Error::TokenExists)
+            };
+            self.ensure_supply_for(1)?;
+            self.add_token_to(to, id)?;
+            self.all_tokens_index.insert(id, self.all_tokens.len());
+            self.all_tokens.push(id);
+            self.total_minted = self.total_minted.saturating_add(1);
+            self.original_minter.insert(id, self.env().caller());
+            self.emit_transfer(None, Some(*to), id);
+            self.env().emit_event(Mint {
+                to: *to,
+                id,
+                metadata: *self.matedatas.get(&id).unwrap_or(&0),
+            });
+            Ok(())
+        }
+
+        /// Fails with `Error::MaxSupplyReached` if minting `count` more tokens exceeds the cap.
+        fn ensure_supply_for(&self, count: u32) -> Result<(), Error> {
+            match self.max_supply {
+                Some(max) if self.total_supply().saturating_add(count) > max => {
+                    Err(Error::MaxSupplyReached)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Removes token `id` from `all_tokens`, moving the last token into its place.
+        fn remove_token_from_all_tokens(&mut self, id: TokenId) -> Result<(), Error> {
+            let index = self.all_tokens_index.take(&id).ok_or(Error::CannotFetchValue)?;
+            self.all_tokens.swap_remove_drop(index).ok_or(Error::CannotRemove)?;
+            if let Some(moved) = self.all_tokens.get(index) {
+                self.all_tokens_index.insert(*moved, index);
+            }
+            Ok(())
+        }
+
+        /// Removes every operator approval granted to `operator`.
+        fn revoke_operator(&mut self, operator: AccountId) {
+            let owners: Vec<AccountId> = self
+                .operator_approvals
+                .keys()
+                .filter(|(_, approved)| *approved == operator)
+                .map(|(owner, _)| *owner)
+                .collect();
+            for owner in owners {
+                if let Some((true, _)) = self.operator_approvals.take(&(owner, operator)) {
+                    self.env().emit_event(ApprovalForAll {
+                        owner,
+                        operator,
+                        approved: false,
+                    });
+                }
+            }
+        }
+
+        /// Approves or disapproves the operator to transfer all tokens of the caller.
+        fn approve_for_all(
+            &mut self,
+            to: AccountId,
+            approved: bool,
+            deadline: u64,
+        ) -> Result<(), Error> {
+            self.when_approvals_not_paused()?;
+            let caller = self.env().caller();
+            if to == caller || to == AccountId::from(ZERO_ADDRESS) {
+                return Err(Error::NotAllowed)
+            }
+            self.operator_approvals.insert((caller, to), (approved, deadline));
+            self.env().emit_event(ApprovalForAll {
+                owner: caller,
+                operator: to,
+                approved,
+            });
+            Ok(())
+        }
+
+        /// Approve the passed AccountId to transfer the specified token on behalf of the message's sender.
+        fn approve_for(&mut self, to: &AccountId, id: TokenId, deadline: u64) -> Result<(), Error> {
+            self.when_approvals_not_paused()?;
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if owner != caller {
+                return Err(Error::NotAllowed)
+            };
+            if *to == AccountId::from(ZERO_ADDRESS) || *to == owner {
+                return Err(Error::NotAllowed)
+            };
+            if self.approval_mode == ApprovalMode::Multi && deadline != u64::MAX {
+                return Err(Error::NotAllowed)
+            };
+            self.ensure_not_soulbound(id)?;
+            self.ensure_not_locked(id)?;
+
+            if !self.insert_approval(&owner, id, to, deadline) {
+                return Ok(())
+            };
+            self.env().emit_event(Approval {
+                from: caller,
+                to: *to,
+                id,
+            });
+            Ok(())
+        }
+
+        /// Removes existing approval from token `id`.
+        ///
+        /// Approvals are keyed by the current owner, so this has to run
+        /// before the token changes hands.
+        fn clear_approval(&mut self, id: TokenId) -> Result<(), Error> {
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if !self.has_approval(&owner, id) {
+                return Ok(())
+            };
+            match self.approval_mode {
+                ApprovalMode::Overwrite => {
+                    self.approvals_token.take(&(owner, id));
+                }
+                ApprovalMode::Multi => {
+                    self.approvals_token_multi.take(&(owner, id));
+                }
+            }
+            self.env().emit_event(Approval {
+                from: owner,
+                to: AccountId::from(ZERO_ADDRESS),
+                id,
+            });
+            Ok(())
+        }
+
+        /// Approves `to` for token `id` of `owner` according to the approval mode.
+        /// Returns `false` if `to` was already approved that way.
+        fn insert_approval(&mut self, owner: &AccountId, id: TokenId, to: &AccountId, deadline: u64) -> bool {
+            match self.approval_mode {
+                ApprovalMode::Overwrite => {
+                    if self.approvals_token.get(&(*owner, id)) == Some(&(*to, deadline)) {
+                        return false
+                    };
+                    self.approvals_token.insert((*owner, id), (*to, deadline));
+                    true
+                }
+                ApprovalMode::Multi => {
+                    let mut approved =
+                        self.approvals_token_multi.get(&(*owner, id)).cloned().unwrap_or_default();
+                    if approved.contains(to) {
+                        return false
+                    };
+                    approved.push(*to);
+                    self.approvals_token_multi.insert((*owner, id), approved);
+                    true
+                }
+            }
+        }
+
+        /// Returns every `(account, deadline)` approved for token `id` of `owner`, expired or not.
+        fn raw_approvals(&self, owner: &AccountId, id: TokenId) -> Vec<(AccountId, u64)> {
+            match self.approval_mode {
+                ApprovalMode::Overwrite => self.approvals_token.get(&(*owner, id)).cloned().into_iter().collect(),
+                ApprovalMode::Multi => self
+                    .approvals_token_multi
+                    .get(&(*owner, id))
+                    .map(|approved| approved.iter().map(|user| (*user, u64::MAX)).collect())
+                    .unwrap_or_default(),
+            }
+        }
+
+        /// Returns `true` if token `id` of `owner` has any approval, expired or not.
+        fn has_approval(&self, owner: &AccountId, id: TokenId) -> bool {
+            match self.approval_mode {
+                ApprovalMode::Overwrite => self.approvals_token.contains_key(&(*owner, id)),
+                ApprovalMode::Multi => self.approvals_token_multi.contains_key(&(*owner, id)),
+            }
+        }
+
+        /// Sets the metadata of token `id`, keeping `metadata_to_token` up to date.
+        fn insert_metadata(&mut self, id: TokenId, value: u32) {
+            if let Some(previous) = self.matedatas.insert(id, value) {
+                self.forget_metadata(id, previous);
+            }
+            self.metadata_to_token.insert(value, id);
+        }
+
+        /// Removes the metadata of token `id` together with its `metadata_to_token` entry.
+        fn remove_metadata(&mut self, id: TokenId) {
+            if let Some(value) = self.matedatas.take(&id) {
+                self.forget_metadata(id, value);
+            }
+        }
+
+        /// Drops `value` from `metadata_to_token` if it still points to token `id`.
+        fn forget_metadata(&mut self, id: TokenId, value: u32) {
+            if self.metadata_to_token.get(&value) == Some(&id) {
+                self.metadata_to_token.take(&value);
+            }
+        }
+
+        /// inherent initialization of `count` NFT tokens
+        fn inherent_init(&mut self, to: &AccountId, count: u32) -> Result<(), Error> {
+            let end = TOKENID_INIT.checked_add(count).ok_or(Error::NotAllowed)?;
+            for (id, metadata) in (TOKENID_INIT..end).zip(MATEDATA_INIT..) {
+                self.insert_metadata(id, metadata);
+                self.mint_token(to, id)?;
+            }
+            Ok(())
+        }
+        /// Fails with `Error::NotContractOwner` unless the caller is the contract owner.
+        fn only_owner(&self) -> Result<(), Error> {
+            // Once renounced, nobody owns the contract.
+            if self.env().caller() != self.owner || self.owner == AccountId::from(ZERO_ADDRESS) {
+                return Err(Error::NotContractOwner)
+            };
+            Ok(())
+        }
+
+        /// Fails with `Error::NotAllowed` unless the caller is the contract owner or a minter.
+        fn only_minter(&self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner && !self.is_minter(caller) {
+                return Err(Error::NotAllowed)
+            };
+            Ok(())
+        }
+
+        /// Pauses or unpauses every operation at once.
+        fn set_all_paused(&mut self, paused: bool) {
+            self.transfers_paused = paused;
+            self.mints_paused = paused;
+            self.approvals_paused = paused;
+        }
+
+        /// Fails with `Error::Paused` while transfers are paused.
+        fn when_transfers_not_paused(&self) -> Result<(), Error> {
+            if self.transfers_paused {
+                return Err(Error::Paused)
+            };
+            Ok(())
+        }
+
+        /// Fails with `Error::Paused` while minting is paused.
+        fn when_mints_not_paused(&self) -> Result<(), Error> {
+            if self.mints_paused {
+                return Err(Error::Paused)
+            };
+            Ok(())
+        }
+
+        /// Sets the allowlist quota of `who`, dropping it once used up.
+        fn set_allowlist_quota(&mut self, who: AccountId, quota: u32) {
+            if quota == 0 {
+                self.allowlist.take(&who);
+            } else {
+                self.allowlist.insert(who, quota);
+            }
+        }
+
+        /// Fails with `Error::MintNotOpen` outside of the mint window.
+        fn when_mint_open(&self) -> Result<(), Error> {
+            let block = self.env().block_number();
+            if self.mint_start_block.is_some_and(|start| block < start)
+                || self.mint_end_block.is_some_and(|end| block > end)
+            {
+                return Err(Error::MintNotOpen)
+            };
+            Ok(())
+        }
+
+        /// Fails with `Error::Paused` while approvals are paused.
+        fn when_approvals_not_paused(&self) -> Result<(), Error> {
+            if self.approvals_paused {
+                return Err(Error::Paused)
+            };
+            Ok(())
+        }
+
+        /// Fails with `Error::MetadataFrozen` once the metadata has been frozen.
+        fn when_metadata_not_frozen(&self) -> Result<(), Error> {
+            if self.metadata_frozen {
+                return Err(Error::MetadataFrozen)
+            };
+            Ok(())
+        }
+
+        /// Fails with `Error::MetadataFrozen` once the metadata of token `id` has been frozen.
+        fn when_token_metadata_not_frozen(&self, id: TokenId) -> Result<(), Error> {
+            if self.token_metadata_frozen(id) {
+                return Err(Error::MetadataFrozen)
+            };
+            Ok(())
+        }
+
+        /// Returns up to `limit` tokens of `owner` starting at index `start`.
+        fn owned_tokens_from(&self, owner: AccountId, start: u32, limit: u32) -> Vec<TokenId> {
+            (start..self.balance_of_or_zero(&owner))
+                .take(limit as usize)
+                .filter_map(|index| self.owned_tokens.get(&(owner, index)).cloned())
+                .collect()
+        }
+
+        /// Returns up to `limit` tokens in the order of `token_by_index`, skipping the first `start`.
+        fn all_tokens_from(&self, start: u32, limit: u32) -> Vec<TokenId> {
+            (start..self.all_tokens.len())
+                .take(limit as usize)
+                .filter_map(|index| self.all_tokens.get(index).cloned())
+                .collect()
+        }
+
+        /// Returns the total number of tokens from an account.
+        fn balance_of_or_zero(&self, of: &AccountId) -> u32 {
+            *self.owned_tokens_count.get(of).unwrap_or(&0)
+        }
+
+        /// check the approved for the user
+        fn approved_for_token(&self,id: TokenId,user: AccountId) -> bool {
+            if user == AccountId::from(ZERO_ADDRESS) {
+                return false
+            }
+            self.get_approvals(id).contains(&user)
+        }
+        /// Gets an operator on other Account's behalf.
+        fn approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            match self.operator_expiry(owner, operator) {
+                Some(deadline) => self.env().block_timestamp() <= deadline,
+                None => false,
+            }
+        }
+
+        /// check whether the user is an approved operator of the owner of token `id`
+        fn approved_for_operator(&self, id: TokenId, user: AccountId) -> bool {
+            match self.owner_of(id) {
+                Some(owner) => self.approved_for_all(owner, user),
+                None => false,
+            }
+        }
+
+        /// Returns true if token `id` exists or false if it does not.
+        fn exists(&self, id: TokenId) -> bool {
+            self.token_owner.contains_key(&id)
+        }
+    }
+
+    /// Returns `true` if calling an account failed with `error` because it is not a contract.
+    ///
+    /// ink! 3.0.0-rc3 offers no `is_contract` or code hash lookup, so whether a
+    /// receiver hook applies is told from the outcome of calling it.
+    fn is_plain_account(error: &ink_env::Error) -> bool {
+        matches!(error, ink_env::Error::NotCallable | ink_env::Error::CodeNotFound)
+    }
+
+    /// Returns `true` if `uri` is not blank.
+    pub fn validate_uri(uri: &str) -> bool {
+        !uri.trim().is_empty()
+    }
+
+    /// Returns `uri` without surrounding whitespace, or `Error::InvalidUri` if it is blank.
+    fn normalize_uri(uri: &str) -> Result<String, Error> {
+        if !validate_uri(uri) {
+            return Err(Error::InvalidUri)
+        };
+        Ok(String::from(uri.trim()))
+    }
+
+    /// Appends `value` to `json` as a quoted and escaped JSON string.
+    fn push_json_string(json: &mut String, value: &str) {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        json.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => json.push_str("\\\""),
+                '\\' => json.push_str("\\\\"),
+                c if (c as u32) < 0x20 => {
+                    json.push_str("\\u00");
+                    json.push(HEX[(c as usize) >> 4] as char);
+                    json.push(HEX[(c as usize) & 0xf] as char);
+                }
+                c => json.push(c),
+            }
+        }
+        json.push('"');
+    }
+
+    /// Returns `basis_points` of `sale_price`, rounded down.
+    fn royalty_amount(sale_price: Balance, basis_points: u16) -> Balance {
+        let denominator = Balance::from(ROYALTY_DENOMINATOR);
+        let basis_points = Balance::from(basis_points);
+        // Split the price to avoid overflowing on huge sale prices.
+        sale_price / denominator * basis_points
+            + sale_price % denominator * basis_points / denominator
+    }
+
+    /// Decrease token counter from the `of` AccountId.
+    ///
+    /// Fails with `Error::BalanceUnderflow` instead of wrapping if the counter is already zero.
+    fn decrease_counter_of(
+        hmap: &mut StorageHashMap<AccountId, u32>,
         of: &AccountId,
     ) -> Result<(), Error> {
         let count = (*hmap).get_mut(of).ok_or(Error::CannotFetchValue)?;
-        *count -= 1;
+        *count = count.checked_sub(1).ok_or(Error::BalanceUnderflow)?;
+        Ok(())
+    }
+
+    /// Increase token counter from the `of` AccountId.
+    fn increase_counter_of(entry: Entry<AccountId, u32>) -> Result<(), Error> {
+        match entry {
+            Entry::Occupied(mut occupied) => {
+                let count = occupied.get_mut();
+                *count = count.checked_add(1).ok_or(Error::BalanceOverflow)?;
+            }
+            Entry::Vacant(vacant) => {
+                vacant.insert(1);
+            }
+        }
         Ok(())
     }
 
-    /// Increase token counter from the `of` AccountId.
-    fn increase_counter_of(entry: Entry<AccountId, u32>) {
-        entry.and_modify(|v| *v += 1).or_insert(1);
-    }
+    /// Unit tests
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+
+        type Event = <Simple_NFT as ::ink_lang::BaseEvent>::Type;
+
+        /// Number of events emitted while minting the initial tokens.
+        const INIT_EVENTS: usize = 20;
+
+        /// Decodes all events recorded after the initial tokens were minted.
+        fn recorded_events() -> Vec<Event> {
+            ink_env::test::recorded_events()
+                .skip(INIT_EVENTS)
+                .map(|event| {
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("encountered invalid contract event data buffer")
+                })
+                .collect()
+        }
+
+        #[ink::test]
+        fn init_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Token 1 does not exists.
+            assert_eq!(nft_token.owner_of(1), None);
+            // Alice has owns tokens.
+            assert_eq!(nft_token.balance_of(accounts.alice), 10);
+            for i in 0..10 {
+                assert_eq!(nft_token.owner_of(TOKENID_INIT+i), Some(accounts.alice));
+            }
+        }
+
+        #[ink::test]
+        fn try_owner_of_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.try_owner_of(TOKENID_INIT), Ok(accounts.alice));
+            assert_eq!(nft_token.try_owner_of(9999), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn new_with_count_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance with three initial tokens.
+            let mut nft_token = Simple_NFT::new_with_count(3);
+            assert_eq!(nft_token.balance_of(accounts.alice), 3);
+            for id in 188..191 {
+                assert_eq!(nft_token.owner_of(id), Some(accounts.alice));
+            }
+            assert_eq!(nft_token.owner_of(191), None);
+            // Auto-assigned ids continue after the initial tokens.
+            assert_eq!(nft_token.mint_next(), Ok(191));
+            // Counts the ids or the cap cannot hold are rejected.
+            assert!(matches!(
+                Simple_NFT::try_new_init(None, None, None, accounts.alice, u32::MAX - 100),
+                Err(Error::NotAllowed)
+            ));
+            assert!(matches!(
+                Simple_NFT::try_new_init(None, None, Some(2), accounts.alice, 3),
+                Err(Error::MaxSupplyReached)
+            ));
+        }
+
+        #[ink::test]
+        fn new_with_initial_owner_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Alice deploys the contract for Bob.
+            let mut nft_token = Simple_NFT::new_with_initial_owner(accounts.bob);
+            assert_eq!(nft_token.balance_of(accounts.alice), 0);
+            assert_eq!(nft_token.balance_of(accounts.bob), 10);
+            for i in 0..10 {
+                assert_eq!(nft_token.owner_of(TOKENID_INIT+i), Some(accounts.bob));
+            }
+            // Alice still administers the contract, but Bob holds the tokens.
+            assert_eq!(nft_token.owner(), accounts.alice);
+            assert_eq!(nft_token.transfer(accounts.eve, TOKENID_INIT), Err(Error::NotAllowed));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.transfer(accounts.eve, TOKENID_INIT), Ok(()));
+        }
+
+        #[ink::test]
+        fn new_with_metadata_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // A contract created with `new` has no name and symbol.
+            let nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.name(), None);
+            assert_eq!(nft_token.symbol(), None);
+            // Create a new contract instance with name and symbol.
+            let nft_token = Simple_NFT::new_with_metadata("TrustNFT".into(), "TNFT".into());
+            assert_eq!(nft_token.name(), Some(String::from("TrustNFT")));
+            assert_eq!(nft_token.symbol(), Some(String::from("TNFT")));
+            // The initial tokens are minted as usual.
+            assert_eq!(nft_token.balance_of(accounts.alice), 10);
+        }
+
+        #[ink::test]
+        fn transfer_ownership_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // The deployer owns the contract.
+            assert_eq!(nft_token.owner(), accounts.alice);
+            // Alice nominates Bob, but still owns the contract.
+            assert_eq!(nft_token.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(nft_token.pending_owner(), Some(accounts.bob));
+            assert_eq!(nft_token.owner(), accounts.alice);
+            assert!(matches!(
+                recorded_events()[..],
+                [Event::OwnershipTransferStarted(OwnershipTransferStarted { previous, new })]
+                    if previous == accounts.alice && new == accounts.bob
+            ));
+            // Bob accepts the contract.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.accept_ownership(), Ok(()));
+            assert_eq!(nft_token.owner(), accounts.bob);
+            assert_eq!(nft_token.pending_owner(), None);
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::OwnershipTransferred(OwnershipTransferred { previous, new }))
+                    if *previous == accounts.alice && *new == accounts.bob
+            ));
+            // Bob is now allowed to mint.
+            assert_eq!(nft_token.mint(1), Ok(()));
+        }
+
+        #[ink::test]
+        fn accept_ownership_fails_for_non_pending_owner() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Nobody can accept before a nomination.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.accept_ownership(), Err(Error::NotAllowed));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.transfer_ownership(accounts.bob), Ok(()));
+            // Only Bob can accept, not even Alice.
+            assert_eq!(nft_token.accept_ownership(), Err(Error::NotAllowed));
+            set_sender(accounts.eve);
+            assert_eq!(nft_token.accept_ownership(), Err(Error::NotAllowed));
+            assert_eq!(nft_token.owner(), accounts.alice);
+            // A new nomination replaces Bob's.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.transfer_ownership(accounts.eve), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.accept_ownership(), Err(Error::NotAllowed));
+            set_sender(accounts.eve);
+            assert_eq!(nft_token.accept_ownership(), Ok(()));
+            assert_eq!(nft_token.owner(), accounts.eve);
+        }
+
+        #[ink::test]
+        fn renounce_ownership_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.renounce_ownership(), Err(Error::NotContractOwner));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.renounce_ownership(), Ok(()));
+            assert_eq!(nft_token.owner(), AccountId::from(ZERO_ADDRESS));
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::OwnershipTransferred(OwnershipTransferred { previous, new }))
+                    if *previous == accounts.alice && *new == AccountId::from(ZERO_ADDRESS)
+            ));
+            // Alice can no longer administer the contract.
+            assert_eq!(nft_token.pause(), Err(Error::NotContractOwner));
+            assert_eq!(nft_token.renounce_ownership(), Err(Error::NotContractOwner));
+            // Her tokens are still hers.
+            assert_eq!(nft_token.transfer(accounts.bob, TOKENID_INIT), Ok(()));
+        }
+
+        #[ink::test]
+        fn withdraw_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            set_balance(contract_id(), 1_000_000);
+            let django_balance = get_balance(accounts.django);
+            // Only the contract owner can withdraw, and no more than the contract holds.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.withdraw(400_000, accounts.bob), Err(Error::NotContractOwner));
+            set_sender(accounts.alice);
+            assert_eq!(
+                nft_token.withdraw(1_000_001, accounts.django),
+                Err(Error::InsufficientPayment)
+            );
+            assert_eq!(nft_token.withdraw(400_000, accounts.django), Ok(()));
+            assert_eq!(get_balance(contract_id()), 600_000);
+            assert_eq!(get_balance(accounts.django), django_balance + 400_000);
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Withdrawn(Withdrawn { to, amount }))
+                    if *to == accounts.django && *amount == 400_000
+            ));
+        }
+
+        #[ink::test]
+        fn transfer_ownership_fails_for_non_owner() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Bob cannot take over the contract.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_ownership(accounts.bob),
+                Err(Error::NotContractOwner)
+            );
+            // Alice cannot hand it over to the zero address.
+            set_sender(accounts.alice);
+            assert_eq!(
+                nft_token.transfer_ownership(AccountId::from(ZERO_ADDRESS)),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(nft_token.owner(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn pause_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert!(!nft_token.paused());
+            assert_eq!(nft_token.approve(accounts.bob, token_id + 1), Ok(()));
+            // Alice pauses the contract.
+            assert_eq!(nft_token.pause(), Ok(()));
+            assert!(nft_token.paused());
+            // Transfers, approvals and minting are blocked.
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Err(Error::Paused));
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Err(Error::Paused));
+            assert_eq!(nft_token.mint(1), Err(Error::Paused));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id + 1),
+                Err(Error::Paused)
+            );
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.bob, token_id + 1, Vec::new()),
+                Err(Error::Paused)
+            );
+            // Reading still works.
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            assert_eq!(nft_token.balance_of(accounts.alice), 10);
+            // Alice unpauses the contract and the transfer goes through.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.unpause(), Ok(()));
+            assert!(!nft_token.paused());
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn pause_mints_only_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.set_mints_paused(true), Ok(()));
+            assert!(nft_token.paused());
+            assert!(nft_token.mints_paused());
+            assert!(!nft_token.transfers_paused());
+            assert!(!nft_token.approvals_paused());
+            // Every mint path fails ...
+            assert_eq!(nft_token.mint(1), Err(Error::Paused));
+            assert_eq!(nft_token.batch_mint(vec![1]), Err(Error::Paused));
+            assert_eq!(nft_token.mint_range(1, 2), Err(Error::Paused));
+            // ... while transfers and approvals still work.
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.approve(accounts.bob, token_id + 1), Ok(()));
+            assert_eq!(nft_token.set_mints_paused(false), Ok(()));
+            assert!(!nft_token.paused());
+            assert_eq!(nft_token.mint(1), Ok(()));
+        }
+
+        #[ink::test]
+        fn pause_transfers_and_approvals_separately() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.set_transfers_paused(true), Ok(()));
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Err(Error::Paused));
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.mint(1), Ok(()));
+            assert_eq!(nft_token.set_transfers_paused(false), Ok(()));
+            assert_eq!(nft_token.set_approvals_paused(true), Ok(()));
+            assert_eq!(nft_token.approve(accounts.bob, token_id + 1), Err(Error::Paused));
+            assert_eq!(nft_token.set_approval_for_all(accounts.bob, true), Err(Error::Paused));
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            // Only the contract owner can pause single operations.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.set_transfers_paused(true), Err(Error::NotContractOwner));
+            assert_eq!(nft_token.set_mints_paused(true), Err(Error::NotContractOwner));
+            assert_eq!(nft_token.set_approvals_paused(false), Err(Error::NotContractOwner));
+        }
+
+        #[ink::test]
+        fn pause_fails_for_non_owner() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.pause(), Err(Error::NotContractOwner));
+            assert_eq!(nft_token.unpause(), Err(Error::NotContractOwner));
+            assert!(!nft_token.paused());
+        }
+
+        #[ink::test]
+        fn royalty_info_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // No royalty is due by default.
+            assert_eq!(
+                nft_token.royalty_info(TOKENID_INIT, 1000),
+                (AccountId::from(ZERO_ADDRESS), 0)
+            );
+            // Alice sets a royalty of 2.5% paid to Charlie.
+            assert_eq!(nft_token.set_default_royalty(accounts.charlie, 250), Ok(()));
+            assert_eq!(nft_token.royalty_info(TOKENID_INIT, 1000), (accounts.charlie, 25));
+            assert_eq!(
+                nft_token.royalty_info(TOKENID_INIT, Balance::MAX),
+                (accounts.charlie, Balance::MAX / 40)
+            );
+        }
+
+        #[ink::test]
+        fn token_royalty_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let (token_a, token_b) = (TOKENID_INIT, TOKENID_INIT + 1);
+            assert_eq!(nft_token.set_default_royalty(accounts.charlie, 250), Ok(()));
+            // Bob owns token A and sets a 5% royalty for it.
+            assert_eq!(nft_token.transfer(accounts.bob, token_a), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.set_token_royalty(token_a, accounts.bob, 500), Ok(()));
+            assert_eq!(nft_token.royalty_info(token_a, 1000), (accounts.bob, 50));
+            // Token B falls back to the default.
+            assert_eq!(nft_token.royalty_info(token_b, 1000), (accounts.charlie, 25));
+            // Bob cannot set the royalty of Alice's token.
+            assert_eq!(
+                nft_token.set_token_royalty(token_b, accounts.bob, 500),
+                Err(Error::NotOwner)
+            );
+            // But the contract owner can set it for any token.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_token_royalty(token_a, accounts.eve, 100), Ok(()));
+            assert_eq!(nft_token.royalty_info(token_a, 1000), (accounts.eve, 10));
+            assert_eq!(
+                nft_token.set_token_royalty(token_b, accounts.eve, 10_001),
+                Err(Error::InvalidRoyalty)
+            );
+        }
+
+        #[ink::test]
+        fn invalid_royalty_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Royalties above 100% are rejected.
+            assert_eq!(
+                nft_token.set_default_royalty(accounts.charlie, 10_001),
+                Err(Error::InvalidRoyalty)
+            );
+            // Only the contract owner may set royalties.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.set_default_royalty(accounts.bob, 250),
+                Err(Error::NotContractOwner)
+            );
+            assert_eq!(
+                nft_token.royalty_info(TOKENID_INIT, 1000),
+                (AccountId::from(ZERO_ADDRESS), 0)
+            );
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Token 1 does not exists.
+            assert_eq!(nft_token.owner_of(1), None);
+            // Alice creates token 1.
+            assert_eq!(nft_token.mint(1), Ok(()));
+            assert_eq!(nft_token.owner_of(1), Some(accounts.alice));
+            assert_eq!(nft_token.balance_of(accounts.alice), 11);
+            // The Transfer and Mint events take place.
+            assert_eq!(2, recorded_events().len());
+            // Minting an existing token fails.
+            assert_eq!(nft_token.mint(1), Err(Error::TokenExists));
+            assert_eq!(nft_token.mint(TOKENID_INIT), Err(Error::TokenExists));
+        }
+
+        #[ink::test]
+        fn mint_emits_mint_event() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_to(accounts.bob, 1), Ok(()));
+            let events = recorded_events();
+            assert_eq!(events.len(), 2);
+            assert!(matches!(
+                events[0],
+                Event::Transfer(Transfer { from: None, to: Some(to), id: 1 }) if to == accounts.bob
+            ));
+            assert!(matches!(
+                events[1],
+                Event::Mint(Mint { to, id: 1, metadata: 0 }) if to == accounts.bob
+            ));
+        }
+
+        #[ink::test]
+        fn init_emits_mint_events() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let _nft_token = Simple_NFT::new();
+            let mints = ink_env::test::recorded_events()
+                .map(|event| {
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("encountered invalid contract event data buffer")
+                })
+                .filter_map(|event| {
+                    match event {
+                        Event::Mint(Mint { to, id, metadata }) => Some((to, id, metadata)),
+                        _ => None,
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                mints,
+                (0..10)
+                    .map(|i| (accounts.alice, TOKENID_INIT + i, MATEDATA_INIT + i))
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(ink_env::test::recorded_events().count(), INIT_EVENTS);
+        }
+
+        #[ink::test]
+        fn mint_fails_for_non_owner() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Only Alice, the contract owner, may mint.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.mint(1), Err(Error::NotAllowed));
+            assert_eq!(nft_token.mint_to(accounts.bob, 1), Err(Error::NotAllowed));
+            assert_eq!(nft_token.owner_of(1), None);
+        }
+
+        #[ink::test]
+        fn mint_and_burn_errors_are_specific() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance that is already full.
+            let mut nft_token = Simple_NFT::new_capped(10);
+            // Minting an existing token fails as such on every mint path.
+            assert_eq!(nft_token.mint(TOKENID_INIT), Err(Error::TokenExists));
+            assert_eq!(nft_token.mint_to(accounts.bob, TOKENID_INIT), Err(Error::TokenExists));
+            assert_eq!(nft_token.mint_soulbound(TOKENID_INIT), Err(Error::TokenExists));
+            assert_eq!(nft_token.mint_with_quantity(TOKENID_INIT, 0), Err(Error::TokenExists));
+            assert_eq!(nft_token.batch_mint(vec![TOKENID_INIT]), Err(Error::TokenExists));
+            assert_eq!(nft_token.mint_range(TOKENID_INIT, 1), Err(Error::TokenExists));
+            // Only new tokens run into the cap.
+            assert_eq!(nft_token.mint(1), Err(Error::MaxSupplyReached));
+            // Burning a missing token or someone else's fails distinctly.
+            assert_eq!(nft_token.burn(1), Err(Error::TokenNotFound));
+            assert_eq!(nft_token.burn_from(accounts.alice, 1), Err(Error::TokenNotFound));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.burn(TOKENID_INIT), Err(Error::NotOwner));
+            assert_eq!(nft_token.burn_from(accounts.bob, TOKENID_INIT), Err(Error::NotOwner));
+            assert_eq!(nft_token.burn_from(accounts.alice, TOKENID_INIT), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn mint_window_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let now = ink_env::block_number::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block number");
+            // Only the contract owner sets the window, which cannot end before it starts.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.set_mint_window(Some(now + 1), Some(now + 2)),
+                Err(Error::NotContractOwner)
+            );
+            set_sender(accounts.alice);
+            assert_eq!(
+                nft_token.set_mint_window(Some(now + 2), Some(now + 1)),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(nft_token.set_mint_window(Some(now + 1), Some(now + 2)), Ok(()));
+            assert_eq!(nft_token.mint_window(), (Some(now + 1), Some(now + 2)));
+            // Before the window no mint path is open.
+            assert_eq!(nft_token.mint(1), Err(Error::MintNotOpen));
+            assert_eq!(nft_token.batch_mint(vec![1]), Err(Error::MintNotOpen));
+            assert_eq!(nft_token.mint_range(1, 1), Err(Error::MintNotOpen));
+            // Inside it minting works.
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(nft_token.mint(1), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(nft_token.mint(2), Ok(()));
+            // After it it fails again.
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(nft_token.mint(3), Err(Error::MintNotOpen));
+            // Open bounds do not limit minting.
+            assert_eq!(nft_token.set_mint_window(Some(now), None), Ok(()));
+            assert_eq!(nft_token.mint(3), Ok(()));
+        }
+
+        #[ink::test]
+        fn allowlist_mint_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.set_allowlist(accounts.bob, 2), Ok(()));
+            assert_eq!(nft_token.allowlist_quota(accounts.bob), 2);
+            // Bob mints twice without being a minter.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.set_allowlist(accounts.bob, 5), Err(Error::NotContractOwner));
+            assert_eq!(nft_token.allowlist_mint(1), Ok(()));
+            assert_eq!(nft_token.allowlist_mint(TOKENID_INIT), Err(Error::TokenExists));
+            assert_eq!(nft_token.allowlist_quota(accounts.bob), 1);
+            assert_eq!(nft_token.allowlist_mint(2), Ok(()));
+            assert_eq!(nft_token.balance_of(accounts.bob), 2);
+            // His quota is used up.
+            assert_eq!(nft_token.allowlist_quota(accounts.bob), 0);
+            assert_eq!(nft_token.allowlist_mint(3), Err(Error::NotAllowed));
+            // Eve is not on the allowlist at all.
+            set_sender(accounts.eve);
+            assert_eq!(nft_token.allowlist_mint(3), Err(Error::NotAllowed));
+            assert_eq!(nft_token.owner_of(3), None);
+        }
+
+        #[ink::test]
+        fn minter_role_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Alice makes Bob a minter.
+            assert!(!nft_token.is_minter(accounts.bob));
+            assert_eq!(nft_token.add_minter(accounts.bob), Ok(()));
+            assert!(nft_token.is_minter(accounts.bob));
+            // Bob is allowed to mint through every mint path.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.mint(1), Ok(()));
+            assert_eq!(nft_token.mint_to(accounts.eve, 2), Ok(()));
+            assert_eq!(nft_token.batch_mint(vec![3, 4]), Ok(()));
+            assert_eq!(nft_token.balance_of(accounts.bob), 3);
+            // Bob cannot manage minters himself.
+            assert_eq!(nft_token.add_minter(accounts.eve), Err(Error::NotContractOwner));
+            assert_eq!(nft_token.remove_minter(accounts.bob), Err(Error::NotContractOwner));
+            // Once Alice removes him, Bob can no longer mint.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.remove_minter(accounts.bob), Ok(()));
+            assert!(!nft_token.is_minter(accounts.bob));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.mint(5), Err(Error::NotAllowed));
+            assert_eq!(nft_token.owner_of(5), None);
+        }
+
+        #[ink::test]
+        fn batch_mint_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Alice mints three tokens at once.
+            assert_eq!(nft_token.batch_mint(vec![300, 301, 302]), Ok(()));
+            assert_eq!(nft_token.balance_of(accounts.alice), 13);
+            assert_eq!(nft_token.total_supply(), 13);
+            for id in 300..303 {
+                assert_eq!(nft_token.owner_of(id), Some(accounts.alice));
+            }
+            // One Transfer and one Mint event per token take place.
+            assert_eq!(6, recorded_events().len());
+        }
+
+        #[ink::test]
+        fn invalid_batch_mint_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // An existing token or a duplicate rolls back the whole batch.
+            assert_eq!(
+                nft_token.batch_mint(vec![300, TOKENID_INIT, 301]),
+                Err(Error::TokenExists)
+            );
+            assert_eq!(nft_token.batch_mint(vec![300, 301, 300]), Err(Error::TokenExists));
+            assert_eq!(nft_token.owner_of(300), None);
+            assert_eq!(nft_token.balance_of(accounts.alice), 10);
+            // Only the contract owner may mint.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.batch_mint(vec![300]), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn mint_range_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_range(400, 5), Ok(()));
+            for id in 400..405 {
+                assert_eq!(nft_token.owner_of(id), Some(accounts.alice));
+            }
+            assert_eq!(nft_token.balance_of(accounts.alice), 15);
+            // An existing token in the range rolls back the whole range.
+            assert_eq!(nft_token.mint_range(402, 5), Err(Error::TokenExists));
+            assert_eq!(nft_token.owner_of(405), None);
+            assert_eq!(nft_token.mint_range(TokenId::MAX, 2), Err(Error::NotAllowed));
+            // Only minters can mint.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.mint_range(500, 1), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn mint_range_respects_max_supply() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new_capped(12);
+            assert_eq!(nft_token.mint_range(400, 3), Err(Error::MaxSupplyReached));
+            assert_eq!(nft_token.total_supply(), 10);
+            assert_eq!(nft_token.mint_range(400, 2), Ok(()));
+            assert_eq!(nft_token.total_supply(), 12);
+        }
+
+        #[ink::test]
+        fn total_minted_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.total_minted(), 10);
+            assert_eq!(nft_token.batch_mint(vec![1, 2, 3]), Ok(()));
+            assert_eq!(nft_token.burn(2), Ok(()));
+            // Burning only lowers the supply.
+            assert_eq!(nft_token.total_minted(), 13);
+            assert_eq!(nft_token.total_supply(), 12);
+        }
+
+        #[ink::test]
+        fn max_supply_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance allowing one token beyond the initial ones.
+            let mut nft_token = Simple_NFT::new_capped(11);
+            assert_eq!(nft_token.total_supply(), 10);
+            // A batch exceeding the cap is rejected as a whole.
+            assert_eq!(nft_token.batch_mint(vec![1, 2]), Err(Error::MaxSupplyReached));
+            assert_eq!(nft_token.owner_of(1), None);
+            // The eleventh token can be minted, the twelfth cannot.
+            assert_eq!(nft_token.mint(1), Ok(()));
+            assert_eq!(nft_token.mint(2), Err(Error::MaxSupplyReached));
+            assert_eq!(nft_token.mint_to(accounts.bob, 2), Err(Error::MaxSupplyReached));
+            assert_eq!(nft_token.total_supply(), 11);
+            // Burning frees up room again.
+            assert_eq!(nft_token.burn(1), Ok(()));
+            assert_eq!(nft_token.mint(2), Ok(()));
+        }
+
+        #[ink::test]
+        fn mint_next_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // IDs continue after the initial tokens.
+            assert_eq!(nft_token.mint_next(), Ok(TOKENID_INIT + 10));
+            assert_eq!(nft_token.mint_next(), Ok(TOKENID_INIT + 11));
+            assert_eq!(nft_token.owner_of(TOKENID_INIT + 11), Some(accounts.alice));
+            // IDs that were minted explicitly are skipped.
+            assert_eq!(nft_token.mint(TOKENID_INIT + 12), Ok(()));
+            assert_eq!(nft_token.mint_next(), Ok(TOKENID_INIT + 13));
+            // Only minters can mint.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.mint_next(), Err(Error::NotAllowed));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.mint_next(), Ok(TOKENID_INIT + 14));
+        }
+
+        #[ink::test]
+        fn soulbound_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_soulbound(1), Ok(()));
+            assert_eq!(nft_token.owner_of(1), Some(accounts.alice));
+            assert!(nft_token.is_soulbound(1));
+            assert!(!nft_token.is_soulbound(TOKENID_INIT));
+            // The token can be neither transferred nor approved.
+            assert_eq!(nft_token.transfer(accounts.bob, 1), Err(Error::Soulbound));
+            assert_eq!(nft_token.approve(accounts.bob, 1), Err(Error::Soulbound));
+            assert_eq!(nft_token.set_approval_for_all(accounts.bob, true), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, 1),
+                Err(Error::Soulbound)
+            );
+            // Only its owner can burn it.
+            assert_eq!(nft_token.burn(1), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.burn(1), Ok(()));
+            assert_eq!(nft_token.owner_of(1), None);
+            assert!(!nft_token.is_soulbound(1));
+            // Only minters can mint soulbound tokens.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.mint_soulbound(2), Err(Error::NotAllowed));
+            assert!(!nft_token.is_soulbound(2));
+        }
+
+        #[ink::test]
+        fn mint_to_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Alice mints token 1 directly into Bob's account.
+            assert_eq!(nft_token.mint_to(accounts.bob, 1), Ok(()));
+            assert_eq!(nft_token.owner_of(1), Some(accounts.bob));
+            assert_eq!(nft_token.balance_of(accounts.bob), 1);
+            assert_eq!(nft_token.balance_of(accounts.alice), 10);
+            // Existing tokens and the zero address are rejected.
+            assert_eq!(nft_token.mint_to(accounts.bob, 1), Err(Error::TokenExists));
+            assert_eq!(
+                nft_token.mint_to(AccountId::from(ZERO_ADDRESS), 2),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(nft_token.owner_of(2), None);
+        }
+
+        #[ink::test]
+        fn minter_of_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.minter_of(TOKENID_INIT), Some(accounts.alice));
+            assert_eq!(nft_token.add_minter(accounts.bob), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.mint(1), Ok(()));
+            assert_eq!(nft_token.minter_of(1), Some(accounts.bob));
+            // Transfers keep the original minter.
+            assert_eq!(nft_token.transfer(accounts.charlie, 1), Ok(()));
+            assert_eq!(nft_token.owner_of(1), Some(accounts.charlie));
+            assert_eq!(nft_token.minter_of(1), Some(accounts.bob));
+            // Burned tokens have none.
+            set_sender(accounts.charlie);
+            assert_eq!(nft_token.burn(1), Ok(()));
+            assert_eq!(nft_token.minter_of(1), None);
+        }
+
+        #[ink::test]
+        fn balance_overflow_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Pretend Bob already holds the maximum number of tokens.
+            nft_token.owned_tokens_count.insert(accounts.bob, u32::MAX);
+            assert_eq!(nft_token.mint_to(accounts.bob, 1), Err(Error::BalanceOverflow));
+            // The count does not wrap and the token is not created.
+            assert_eq!(nft_token.balance_of(accounts.bob), u32::MAX);
+            assert_eq!(nft_token.owner_of(1), None);
+            assert_eq!(nft_token.total_supply(), 10);
+        }
+
+        #[ink::test]
+        fn burn_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Bob cannot burn Alice's token.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.burn(TOKENID_INIT), Err(Error::NotOwner));
+            // Alice burns her token.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.burn(TOKENID_INIT), Ok(()));
+            assert_eq!(nft_token.owner_of(TOKENID_INIT), None);
+            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+            // The token cannot be burned twice.
+            assert_eq!(nft_token.burn(TOKENID_INIT), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn burned_token_is_reminted_clean() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_to(accounts.charlie, 1), Ok(()));
+            assert_eq!(nft_token.set_allowlist(accounts.bob, 1), Ok(()));
+            // Charlie ties the token to himself and burns it.
+            set_sender(accounts.charlie);
+            assert_eq!(nft_token.set_token_royalty(1, accounts.charlie, 1000), Ok(()));
+            assert_eq!(nft_token.set_metadata(1, 77), Ok(()));
+            assert_eq!(nft_token.lock_until(1, u64::MAX), Ok(()));
+            assert_eq!(nft_token.lock(1, accounts.charlie), Ok(()));
+            assert_eq!(nft_token.burn(1), Ok(()));
+            // Bob mints the same id afresh.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.allowlist_mint(1), Ok(()));
+            assert_eq!(nft_token.locker_of(1), None);
+            assert_eq!(nft_token.locked_until(1), None);
+            assert_eq!(nft_token.royalty_info(1, 1000), (AccountId::from(ZERO_ADDRESS), 0));
+            assert_eq!(nft_token.metadata_of(1), None);
+            assert_eq!(nft_token.token_by_metadata(77), None);
+            // Charlie has no hold on Bob's token.
+            set_sender(accounts.charlie);
+            assert_eq!(
+                nft_token.transfer_from(accounts.bob, accounts.charlie, 1),
+                Err(Error::NotApproved)
+            );
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.transfer(accounts.eve, 1), Ok(()));
+        }
+
+        #[ink::test]
+        fn burn_from_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            // Eve is neither approved nor an operator.
+            set_sender(accounts.eve);
+            assert_eq!(nft_token.burn_from(accounts.alice, token_id), Err(Error::NotApproved));
+            // Bob burns Alice's token.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.burn_from(accounts.bob, token_id), Err(Error::NotOwner));
+            assert_eq!(nft_token.burn_from(accounts.alice, token_id), Ok(()));
+            assert!(!nft_token.exists(token_id));
+            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Transfer(Transfer { from: Some(from), to: None, id }))
+                    if *from == accounts.alice && *id == token_id
+            ));
+            assert_eq!(nft_token.burn_from(accounts.alice, token_id), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn supports_interface_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let nft_token = Simple_NFT::new();
+            // ERC-721 and ERC-2981 are supported.
+            assert!(nft_token.supports_interface([0x80, 0xac, 0x58, 0xcd]));
+            assert!(nft_token.supports_interface([0x2a, 0x55, 0x20, 0x5a]));
+            // The invalid ERC-165 ID and unknown IDs are not.
+            assert!(!nft_token.supports_interface([0xff; 4]));
+            assert!(!nft_token.supports_interface([0x12, 0x34, 0x56, 0x78]));
+        }
+
+        #[ink::test]
+        fn token_exists_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let nft_token = Simple_NFT::new();
+            assert!(nft_token.token_exists(TOKENID_INIT));
+            assert!(!nft_token.token_exists(9999));
+        }
+
+        #[ink::test]
+        fn exists_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert!(nft_token.exists(TOKENID_INIT));
+            assert!(!nft_token.exists(2));
+            // Minted tokens exist, burned ones do not.
+            assert_eq!(nft_token.mint(2), Ok(()));
+            assert!(nft_token.exists(2));
+            assert_eq!(nft_token.burn(TOKENID_INIT), Ok(()));
+            assert!(!nft_token.exists(TOKENID_INIT));
+        }
+
+        #[ink::test]
+        fn stats_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.stats(), (10, 1, 0));
+            assert_eq!(nft_token.transfer(accounts.bob, TOKENID_INIT), Ok(()));
+            assert_eq!(nft_token.approve(accounts.eve, TOKENID_INIT + 1), Ok(()));
+            assert_eq!(nft_token.stats(), (10, 2, 1));
+            // Owners without tokens are not counted.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.burn(TOKENID_INIT), Ok(()));
+            assert_eq!(nft_token.stats(), (9, 1, 1));
+        }
+
+        #[ink::test]
+        fn enumeration_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.total_supply(), 10);
+            // Mint three more tokens.
+            for id in 1..=3 {
+                assert_eq!(nft_token.mint(id), Ok(()));
+            }
+            assert_eq!(nft_token.total_supply(), 13);
+            assert_eq!(nft_token.token_by_index(13), None);
+            // Every token is enumerated exactly once.
+            let mut ids = (0..nft_token.total_supply())
+                .map(|index| nft_token.token_by_index(index).expect("index is in bounds"))
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            let mut expected = (1..=3).chain(TOKENID_INIT..TOKENID_INIT + 10).collect::<Vec<_>>();
+            expected.sort_unstable();
+            assert_eq!(ids, expected);
+            // Burning keeps the indices dense.
+            assert_eq!(nft_token.burn(TOKENID_INIT), Ok(()));
+            assert_eq!(nft_token.total_supply(), 12);
+            let mut ids = (0..nft_token.total_supply())
+                .map(|index| nft_token.token_by_index(index).expect("index is in bounds"))
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            expected.retain(|id| *id != TOKENID_INIT);
+            assert_eq!(ids, expected);
+        }
+
+        #[ink::test]
+        fn owner_enumeration_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let tokens_of = |nft_token: &Simple_NFT, owner: AccountId| {
+                let mut ids = (0..nft_token.balance_of(owner))
+                    .map(|index| {
+                        nft_token
+                            .token_of_owner_by_index(owner, index)
+                            .expect("index is in bounds")
+                    })
+                    .collect::<Vec<_>>();
+                ids.sort_unstable();
+                ids
+            };
+            assert_eq!(
+                tokens_of(&nft_token, accounts.alice),
+                (TOKENID_INIT..TOKENID_INIT + 10).collect::<Vec<_>>()
+            );
+            assert_eq!(tokens_of(&nft_token, accounts.bob), vec![]);
+            // Alice transfers a token from the middle of her list to Bob.
+            let token_id = TOKENID_INIT + 3;
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            assert_eq!(
+                tokens_of(&nft_token, accounts.alice),
+                (TOKENID_INIT..TOKENID_INIT + 10)
+                    .filter(|id| *id != token_id)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(nft_token.token_of_owner_by_index(accounts.alice, 9), None);
+            assert_eq!(tokens_of(&nft_token, accounts.bob), vec![token_id]);
+            // Bob sends it back, which leaves his list empty again.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.transfer(accounts.alice, token_id), Ok(()));
+            assert_eq!(tokens_of(&nft_token, accounts.bob), vec![]);
+            assert_eq!(nft_token.token_of_owner_by_index(accounts.bob, 0), None);
+            assert_eq!(
+                tokens_of(&nft_token, accounts.alice),
+                (TOKENID_INIT..TOKENID_INIT + 10).collect::<Vec<_>>()
+            );
+        }
+
+        #[ink::test]
+        fn burn_keeps_enumeration_consistent() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_range(400, 5), Ok(()));
+            for id in 400..405 {
+                assert_eq!(nft_token.transfer(accounts.bob, id), Ok(()));
+            }
+            // Bob burns the middle one of his five tokens.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.burn(402), Ok(()));
+            // Bob's tokens are listed exactly once at valid indexes.
+            let mut owned = (0..nft_token.balance_of(accounts.bob))
+                .map(|index| {
+                    let id = nft_token
+                        .token_of_owner_by_index(accounts.bob, index)
+                        .expect("index is in bounds");
+                    assert_eq!(nft_token.owned_tokens_index.get(&id), Some(&index));
+                    id
+                })
+                .collect::<Vec<_>>();
+            owned.sort_unstable();
+            assert_eq!(owned, vec![400, 401, 403, 404]);
+            assert_eq!(nft_token.token_of_owner_by_index(accounts.bob, 4), None);
+            // So are all tokens.
+            let mut all = (0..nft_token.total_supply())
+                .map(|index| {
+                    let id = nft_token.token_by_index(index).expect("index is in bounds");
+                    assert_eq!(nft_token.all_tokens_index.get(&id), Some(&index));
+                    id
+                })
+                .collect::<Vec<_>>();
+            all.sort_unstable();
+            let mut expected = (TOKENID_INIT..TOKENID_INIT + 10).collect::<Vec<_>>();
+            expected.extend_from_slice(&[400, 401, 403, 404]);
+            assert_eq!(all, expected);
+            assert_eq!(nft_token.token_by_index(14), None);
+        }
+
+        #[ink::test]
+        fn transfer_from_wrong_owner_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.transfer(accounts.eve, token_id + 1), Ok(()));
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            // Bob cannot take Alice's token out of Eve's account.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.eve, accounts.bob, token_id),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            assert_eq!(nft_token.balance_of(accounts.eve), 1);
+            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+            // Neither can he through `safe_transfer_from`, whose receiver is not called.
+            receivers::register(accounts.charlie, receivers::accepting);
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.eve, accounts.charlie, token_id, vec![]),
+                Err(Error::NotOwner)
+            );
+            assert!(!receivers::received().iter().any(|(_, from, ..)| *from == accounts.eve));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+        }
+
+        #[ink::test]
+        fn tokens_of_owner_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let mut tokens = nft_token.tokens_of_owner(accounts.alice);
+            tokens.sort_unstable();
+            assert_eq!(tokens, (TOKENID_INIT..TOKENID_INIT + 10).collect::<Vec<_>>());
+            assert_eq!(nft_token.tokens_of_owner(accounts.bob), vec![]);
+            // Alice's list shrinks when she transfers a token.
+            assert_eq!(nft_token.transfer(accounts.bob, TOKENID_INIT), Ok(()));
+            let tokens = nft_token.tokens_of_owner(accounts.alice);
+            assert_eq!(tokens.len(), 9);
+            assert!(!tokens.contains(&TOKENID_INIT));
+            assert_eq!(nft_token.tokens_of_owner(accounts.bob), vec![TOKENID_INIT]);
+        }
+
+        #[ink::test]
+        fn raw_token_count_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.lock(TOKENID_INIT, accounts.bob), Ok(()));
+            // Both counts agree for now, locked tokens included.
+            for owner in [accounts.alice, accounts.bob].iter() {
+                assert_eq!(nft_token.raw_token_count(*owner), nft_token.balance_of(*owner));
+            }
+            assert_eq!(nft_token.raw_token_count(accounts.alice), 10);
+            assert_eq!(nft_token.raw_token_count(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn balances_of_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.balances_of(vec![accounts.alice, accounts.bob]), vec![10, 0]);
+            assert_eq!(nft_token.balances_of(Vec::new()), Vec::<u32>::new());
+        }
+
+        #[ink::test]
+        fn owners_of_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let nft_token = Simple_NFT::new();
+            // Unknown tokens are reported as `None`.
+            assert_eq!(
+                nft_token.owners_of(vec![TOKENID_INIT, 9999]),
+                vec![Some(accounts.alice), None]
+            );
+            assert_eq!(nft_token.owners_of(Vec::new()), Vec::new());
+        }
+
+        #[ink::test]
+        fn tokens_with_metadata_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(
+                nft_token.tokens_with_metadata(0, 3),
+                (0..3)
+                    .map(|i| (TOKENID_INIT + i, MATEDATA_INIT + i))
+                    .collect::<Vec<_>>()
+            );
+            // Tokens without metadata report zero, pages follow the minting order
+            // rather than the ID and stop at the end.
+            assert_eq!(nft_token.mint_to(accounts.bob, 500), Ok(()));
+            assert_eq!(nft_token.mint_to(accounts.bob, 1), Ok(()));
+            assert_eq!(
+                nft_token.tokens_with_metadata(9, 5),
+                vec![(TOKENID_INIT + 9, MATEDATA_INIT + 9), (500, 0), (1, 0)]
+            );
+        }
+
+        #[ink::test]
+        fn mint_with_uri_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_with_uri(1, "ipfs://a".into()), Ok(()));
+            assert_eq!(nft_token.owner_of(1), Some(accounts.alice));
+            assert_eq!(nft_token.token_uri(1), Some(String::from("ipfs://a")));
+            assert!(matches!(
+                recorded_events()[..],
+                [Event::Transfer(_), Event::Mint(_), Event::MetadataUpdate(MetadataUpdate { id: 1 })]
+            ));
+            // Neither a blank URI nor an existing token mints anything.
+            assert_eq!(nft_token.mint_with_uri(2, " ".into()), Err(Error::InvalidUri));
+            assert_eq!(nft_token.owner_of(2), None);
+            assert_eq!(
+                nft_token.mint_with_uri(TOKENID_INIT, "ipfs://b".into()),
+                Err(Error::TokenExists)
+            );
+            assert_eq!(nft_token.token_uri(TOKENID_INIT), None);
+        }
+
+        #[ink::test]
+        fn mint_with_quantity_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_with_quantity(1, 0), Err(Error::NotAllowed));
+            assert_eq!(nft_token.mint_with_quantity(1, 5), Ok(()));
+            assert_eq!(nft_token.quantity_of(1), 5);
+            // Other tokens are single copies and missing ones have none.
+            assert_eq!(nft_token.quantity_of(TOKENID_INIT), 1);
+            assert_eq!(nft_token.quantity_of(2), 0);
+            // All copies move with the token.
+            assert_eq!(nft_token.transfer(accounts.bob, 1), Ok(()));
+            assert_eq!(nft_token.quantity_of(1), 5);
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.burn(1), Ok(()));
+            assert_eq!(nft_token.quantity_of(1), 0);
+        }
+
+        #[ink::test]
+        fn tokens_of_owner_is_paged() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_range(400, 150), Ok(()));
+            assert_eq!(nft_token.balance_of(accounts.alice), 160);
+            // The unpaged call stops at the cap.
+            let first = nft_token.tokens_of_owner(accounts.alice);
+            assert_eq!(first.len(), MAX_PAGE as usize);
+            assert_eq!(nft_token.tokens_of_owner_paged(accounts.alice, 0, 1000), first);
+            // Walking the pages yields every token exactly once.
+            let mut all = Vec::new();
+            let mut start = 0;
+            loop {
+                let page = nft_token.tokens_of_owner_paged(accounts.alice, start, 70);
+                if page.is_empty() {
+                    break
+                }
+                start += page.len() as u32;
+                all.extend(page);
+            }
+            all.sort_unstable();
+            let mut expected = (TOKENID_INIT..TOKENID_INIT + 10).collect::<Vec<_>>();
+            expected.extend(400..550);
+            assert_eq!(all, expected);
+            // Other paged queries are capped as well.
+            assert_eq!(nft_token.export_owners(0, 1000).len(), MAX_PAGE as usize);
+            assert_eq!(nft_token.tokens_with_metadata(0, 1000).len(), MAX_PAGE as usize);
+        }
+
+        #[ink::test]
+        fn export_owners_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.mint_to(accounts.bob, 1), Ok(()));
+            assert_eq!(nft_token.mint_to(accounts.eve, 500), Ok(()));
+            // Pages follow `token_by_index`, i.e. the minting order here.
+            let first_page = nft_token.export_owners(0, 5);
+            assert_eq!(
+                first_page,
+                (TOKENID_INIT..TOKENID_INIT + 5)
+                    .map(|id| (id, accounts.alice))
+                    .collect::<Vec<_>>()
+            );
+            let second_page = nft_token.export_owners(5, 5);
+            assert_eq!(
+                second_page,
+                (TOKENID_INIT + 5..TOKENID_INIT + 10)
+                    .map(|id| (id, accounts.alice))
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                nft_token.export_owners(10, 5),
+                vec![(1, accounts.bob), (500, accounts.eve)]
+            );
+            assert_eq!(nft_token.export_owners(12, 5), vec![]);
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT + 0;
+            // Alice owns all tokens
+            assert_eq!(nft_token.balance_of(accounts.alice), 10);
+            // Bob does not owns any token
+            assert_eq!(nft_token.balance_of(accounts.bob), 0);
+            // alice own the token
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            // The first Transfer event takes place
+            assert_eq!(0, recorded_events().len());
+            // Alice transfers token 1 to Bob
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            // The second Transfer event takes place
+            assert_eq!(1, recorded_events().len());
+            // bob own the token
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
+            // Bob owns token 1
+            assert_eq!(nft_token.balance_of(accounts.bob), 1);
+            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+        }
+
+        #[ink::test]
+        fn transfer_to_zero_address_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let zero = AccountId::from(ZERO_ADDRESS);
+            assert_eq!(nft_token.transfer(zero, TOKENID_INIT), Err(Error::NotAllowed));
+            assert_eq!(nft_token.approve(zero, TOKENID_INIT), Err(Error::NotAllowed));
+            assert_eq!(nft_token.balance_of(zero), 0);
+            assert_eq!(nft_token.owner_of(TOKENID_INIT), Some(accounts.alice));
+        }
+
+        #[ink::test]
+        fn owner_change_drops_owner_scoped_state() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.charlie, token_id), Ok(()));
+            assert_eq!(nft_token.list_for_sale(token_id, 600_000), Ok(()));
+            // Bob receives the token without Alice's approval and listing.
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.get_approved(token_id), None);
+            assert_eq!(nft_token.price_of(token_id), None);
+            assert!(!nft_token.approvals_token.contains_key(&(accounts.alice, token_id)));
+            // A denied receiver leaves the state of the current owner untouched.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_denied(accounts.eve, true), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.approve(accounts.charlie, token_id), Ok(()));
+            assert_eq!(nft_token.list_for_sale(token_id, 600_000), Ok(()));
+            assert_eq!(nft_token.transfer(accounts.eve, token_id), Err(Error::Denied));
+            assert_eq!(nft_token.get_approved(token_id), Some(accounts.charlie));
+            assert_eq!(nft_token.price_of(token_id), Some(600_000));
+            // Burning drops Bob's approval and listing the same way.
+            assert_eq!(nft_token.burn(token_id), Ok(()));
+            assert_eq!(nft_token.price_of(token_id), None);
+            assert!(!nft_token.approvals_token.contains_key(&(accounts.bob, token_id)));
+        }
+
+        #[ink::test]
+        fn rescue_nft_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Frank is an NFT contract whose token 7 was sent to this contract.
+            foreign::register(accounts.frank, 7, contract_id());
+            foreign::register(accounts.frank, 8, accounts.eve);
+            // Only the contract owner can rescue it.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.rescue_nft(accounts.frank, 7, accounts.bob),
+                Err(Error::NotContractOwner)
+            );
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.rescue_nft(accounts.frank, 7, accounts.bob), Ok(()));
+            assert_eq!(foreign::owner_of(accounts.frank, 7), Some(accounts.bob));
+            // Tokens the contract does not hold and plain accounts cannot be rescued.
+            assert_eq!(
+                nft_token.rescue_nft(accounts.frank, 8, accounts.bob),
+                Err(Error::RescueFailed)
+            );
+            assert_eq!(
+                nft_token.rescue_nft(accounts.django, 7, accounts.bob),
+                Err(Error::RescueFailed)
+            );
+            // Neither can the contract's own tokens.
+            assert_eq!(
+                nft_token.rescue_nft(contract_id(), TOKENID_INIT, accounts.bob),
+                Err(Error::NotAllowed)
+            );
+        }
+
+        #[ink::test]
+        fn approve_and_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve_and_transfer(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
+            assert_eq!(nft_token.get_approved(token_id), None);
+            // Both the approval and the transfer are recorded.
+            let events = recorded_events();
+            assert_eq!(events.len(), 2);
+            assert!(matches!(
+                events[0],
+                Event::Approval(Approval { from, to, id })
+                    if from == accounts.alice && to == accounts.bob && id == token_id
+            ));
+            assert!(matches!(
+                events[1],
+                Event::Transfer(Transfer { from, to, id })
+                    if from == Some(accounts.alice) && to == Some(accounts.bob) && id == token_id
+            ));
+            // Alice no longer owns the token.
+            assert_eq!(
+                nft_token.approve_and_transfer(accounts.eve, token_id),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(recorded_events().len(), 2);
+        }
+
+        #[ink::test]
+        fn lock_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Alice locks her token to the vault Bob.
+            assert_eq!(nft_token.lock(token_id, accounts.bob), Ok(()));
+            assert_eq!(nft_token.locker_of(token_id), Some(accounts.bob));
+            assert_eq!(nft_token.lock(token_id, accounts.eve), Err(Error::TokenLocked));
+            // Alice can no longer move, approve or burn it.
+            assert_eq!(nft_token.transfer(accounts.eve, token_id), Err(Error::TokenLocked));
+            assert_eq!(nft_token.approve(accounts.eve, token_id), Err(Error::TokenLocked));
+            assert_eq!(nft_token.burn(token_id), Err(Error::TokenLocked));
+            // Nor can she unlock it.
+            assert_eq!(nft_token.unlock(token_id), Err(Error::TokenLocked));
+            // Bob unlocks it, after which Alice can transfer it again.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.unlock(token_id), Ok(()));
+            assert_eq!(nft_token.unlock(token_id), Err(Error::NotAllowed));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.transfer(accounts.eve, token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.eve));
+        }
+
+        #[ink::test]
+        fn lock_until_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            // Only the owner can lock the token.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.lock_until(token_id, now + 1), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.lock_until(token_id, now + 1), Ok(()));
+            assert_eq!(nft_token.locked_until(token_id), Some(now + 1));
+            // The lock can be extended but not shortened.
+            assert_eq!(nft_token.lock_until(token_id, now), Err(Error::TokenLocked));
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Err(Error::TokenLocked));
+            // Once the time has come the token can be transferred.
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(nft_token.locked_until(token_id), None);
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn locker_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.set_approval_for_all(accounts.eve, true), Ok(()));
+            assert_eq!(nft_token.lock(token_id, accounts.bob), Ok(()));
+            // Even an approved operator cannot move a locked token.
+            set_sender(accounts.eve);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.eve, token_id),
+                Err(Error::TokenLocked)
+            );
+            // The locker can, which releases the lock.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id),
+                Ok(())
+            );
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
+            assert_eq!(nft_token.locker_of(token_id), None);
+        }
+
+        #[ink::test]
+        fn transfer_count_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // The initial tokens are counted.
+            assert_eq!(nft_token.transfer_count(), 10);
+            assert_eq!(nft_token.transfer(accounts.bob, TOKENID_INIT), Ok(()));
+            assert_eq!(nft_token.transfer_count(), 11);
+            assert_eq!(nft_token.mint(1), Ok(()));
+            assert_eq!(nft_token.burn(1), Ok(()));
+            assert_eq!(nft_token.transfer_count(), 13);
+            // Failed transfers are not counted.
+            assert_eq!(nft_token.transfer(accounts.bob, TOKENID_INIT), Err(Error::NotAllowed));
+            assert_eq!(nft_token.transfer_count(), 13);
+        }
+
+        #[ink::test]
+        fn wrap_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Alice wraps her token in Django's vault.
+            assert_eq!(nft_token.wrap(token_id, accounts.alice), Err(Error::NotAllowed));
+            assert_eq!(nft_token.wrap(token_id, accounts.django), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.django));
+            assert_eq!(nft_token.wrapped_in(token_id), Some(accounts.django));
+            // Neither Alice nor the vault can move or burn it.
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Err(Error::TokenLocked));
+            set_sender(accounts.django);
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Err(Error::TokenLocked));
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Err(Error::TokenLocked));
+            assert_eq!(nft_token.burn(token_id), Err(Error::TokenLocked));
+            // Only the vault can unwrap it, which returns it to Alice.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.unwrap(token_id), Err(Error::NotOwner));
+            set_sender(accounts.django);
+            assert_eq!(nft_token.unwrap(token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            assert_eq!(nft_token.wrapped_in(token_id), None);
+            assert_eq!(nft_token.unwrap(token_id), Err(Error::NotAllowed));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+        }
+
+        #[ink::test]
+        fn buy_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Only the owner can list the token.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.list_for_sale(token_id, 600_000), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.list_for_sale(token_id, 600_000), Ok(()));
+            assert_eq!(nft_token.price_of(token_id), Some(600_000));
+            let alice_balance = get_balance(accounts.alice);
+            // Bob sends 1000000 along with the call, which the contract now holds.
+            set_sender(accounts.bob);
+            set_balance(contract_id(), 1_000_000);
+            let bob_balance = get_balance(accounts.bob);
+            assert_eq!(nft_token.buy(token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
+            assert_eq!(nft_token.price_of(token_id), None);
+            // Alice receives the price and Bob the change.
+            assert_eq!(get_balance(accounts.alice), alice_balance + 600_000);
+            assert_eq!(get_balance(accounts.bob), bob_balance + 400_000);
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Sold(Sold { id, from, to, price }))
+                    if *id == token_id && *from == accounts.alice && *to == accounts.bob && *price == 600_000
+            ));
+            // The token is no longer for sale.
+            set_sender(accounts.eve);
+            assert_eq!(nft_token.buy(token_id), Err(Error::NotForSale));
+        }
+
+        #[ink::test]
+        fn buy_pays_royalty() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Charlie receives a royalty of 10%.
+            assert_eq!(nft_token.set_default_royalty(accounts.charlie, 1000), Ok(()));
+            assert_eq!(nft_token.list_for_sale(token_id, 600_000), Ok(()));
+            let alice_balance = get_balance(accounts.alice);
+            let charlie_balance = get_balance(accounts.charlie);
+            set_sender(accounts.bob);
+            set_balance(contract_id(), 1_000_000);
+            assert_eq!(nft_token.buy(token_id), Ok(()));
+            assert_eq!(get_balance(accounts.charlie), charlie_balance + 60_000);
+            assert_eq!(get_balance(accounts.alice), alice_balance + 540_000);
+        }
+
+        #[ink::test]
+        fn enforce_royalties_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.approve(accounts.bob, token_id + 1), Ok(()));
+            // Only the contract owner manages enforcement and the whitelist.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.set_enforce_royalties(true), Err(Error::NotContractOwner));
+            assert_eq!(
+                nft_token.set_whitelisted_marketplace(accounts.bob, true),
+                Err(Error::NotContractOwner)
+            );
+            set_sender(accounts.alice);
+            assert!(!nft_token.enforce_royalties());
+            assert_eq!(nft_token.set_enforce_royalties(true), Ok(()));
+            assert!(nft_token.enforce_royalties());
+            // Bob is approved, but not a whitelisted marketplace.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id),
+                Err(Error::NotAllowed)
+            );
+            // Alice can still move her own tokens.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.transfer(accounts.eve, token_id + 2), Ok(()));
+            // Once whitelisted, Bob can.
+            assert_eq!(nft_token.set_whitelisted_marketplace(accounts.bob, true), Ok(()));
+            assert!(nft_token.is_whitelisted_marketplace(accounts.bob));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id),
+                Ok(())
+            );
+            // Without enforcement anyone approved can.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_whitelisted_marketplace(accounts.bob, false), Ok(()));
+            assert_eq!(nft_token.set_enforce_royalties(false), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id + 1),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn buy_with_insufficient_payment_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.list_for_sale(token_id, 2_000_000), Ok(()));
+            // Bob only pays 1000000.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.buy(token_id), Err(Error::InsufficientPayment));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            // A cancelled listing cannot be bought.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.cancel_listing(token_id), Ok(()));
+            assert_eq!(nft_token.cancel_listing(token_id), Err(Error::NotForSale));
+            // Neither can a listing the seller transferred away.
+            assert_eq!(nft_token.list_for_sale(token_id + 1, 1), Ok(()));
+            assert_eq!(nft_token.transfer(accounts.eve, token_id + 1), Ok(()));
+            assert_eq!(nft_token.price_of(token_id + 1), None);
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.buy(token_id + 1), Err(Error::NotForSale));
+        }
+
+        #[ink::test]
+        fn atomic_swap_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let my_id = TOKENID_INIT;
+            let their_id = TOKENID_INIT + 1;
+            assert_eq!(nft_token.transfer(accounts.bob, their_id), Ok(()));
+            // Alice needs Bob's approval and to approve the contract herself.
+            assert_eq!(
+                nft_token.atomic_swap(my_id, their_id, accounts.bob),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(nft_token.approve(contract_id(), my_id), Ok(()));
+            assert_eq!(
+                nft_token.atomic_swap(my_id, their_id, accounts.bob),
+                Err(Error::NotApproved)
+            );
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.approve(accounts.alice, their_id), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(
+                nft_token.atomic_swap(my_id, their_id, accounts.eve),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(nft_token.owner_of(my_id), Some(accounts.alice));
+            let events_before = recorded_events().len();
+            assert_eq!(nft_token.atomic_swap(my_id, their_id, accounts.bob), Ok(()));
+            assert_eq!(nft_token.owner_of(my_id), Some(accounts.bob));
+            assert_eq!(nft_token.owner_of(their_id), Some(accounts.alice));
+            // Both approvals are cleared along with a `Transfer` event per token.
+            assert_eq!(nft_token.get_approved(my_id), None);
+            assert_eq!(nft_token.get_approved(their_id), None);
+            let transfers = recorded_events()
+                .into_iter()
+                .skip(events_before)
+                .filter(|event| matches!(event, Event::Transfer(_)))
+                .count();
+            assert_eq!(transfers, 2);
+        }
+
+        #[ink::test]
+        fn transfer_with_memo_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(
+                nft_token.transfer_with_memo(accounts.bob, token_id, vec![0x2a; MAX_MEMO_LEN + 1]),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(
+                nft_token.transfer_with_memo(accounts.bob, token_id, b"deposit-42".to_vec()),
+                Ok(())
+            );
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
+            assert!(matches!(
+                &recorded_events()[..],
+                [Event::Transfer(_), Event::TransferMemo(TransferMemo { id, memo })]
+                    if *id == token_id && memo == b"deposit-42"
+            ));
+        }
+
+        #[ink::test]
+        fn transfer_fee_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Only the contract owner sets the fee, and it needs a treasury.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.set_transfer_fee(600_000, accounts.django),
+                Err(Error::NotContractOwner)
+            );
+            set_sender(accounts.alice);
+            assert_eq!(
+                nft_token.set_transfer_fee(600_000, AccountId::from(ZERO_ADDRESS)),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(nft_token.set_transfer_fee(600_000, accounts.django), Ok(()));
+            assert_eq!(nft_token.transfer_fee(), (600_000, accounts.django));
+            // Alice sends 1000000 along with the call, which the contract now holds.
+            set_balance(contract_id(), 1_000_000);
+            let alice_balance = get_balance(accounts.alice);
+            let django_balance = get_balance(accounts.django);
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
+            // The treasury receives the fee and Alice the change.
+            assert_eq!(get_balance(accounts.django), django_balance + 600_000);
+            assert_eq!(get_balance(accounts.alice), alice_balance + 400_000);
+        }
+
+        #[ink::test]
+        fn transfer_with_insufficient_fee_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            // Alice and Bob only pay 1000000.
+            assert_eq!(nft_token.set_transfer_fee(2_000_000, accounts.django), Ok(()));
+            assert_eq!(
+                nft_token.transfer(accounts.bob, token_id),
+                Err(Error::InsufficientPayment)
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id),
+                Err(Error::InsufficientPayment)
+            );
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            // Without a fee transfers work as before.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_transfer_fee(0, accounts.django), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn transfer_fee_applies_to_every_transfer_path() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Every call sends 1000000 along, enough for three fees.
+            assert_eq!(nft_token.set_transfer_fee(300_000, accounts.django), Ok(()));
+            set_balance(contract_id(), 10_000_000);
+            let django_balance = get_balance(accounts.django);
+            let four = vec![token_id, token_id + 1, token_id + 2, token_id + 3];
+            assert_eq!(nft_token.batch_transfer(accounts.bob, four), Err(Error::InsufficientPayment));
+            assert_eq!(nft_token.balance_of(accounts.bob), 0);
+            assert_eq!(
+                nft_token.batch_transfer(accounts.bob, vec![token_id, token_id + 1, token_id + 2]),
+                Ok(())
+            );
+            assert_eq!(get_balance(accounts.django), django_balance + 900_000);
+            assert_eq!(nft_token.approve_and_transfer(accounts.bob, token_id + 3), Ok(()));
+            assert_eq!(nft_token.transfer_and_call(accounts.bob, token_id + 4, Vec::new()), Ok(()));
+            assert_eq!(get_balance(accounts.django), django_balance + 1_500_000);
+            // Buyers pay the fee on top of the price.
+            assert_eq!(nft_token.list_for_sale(token_id + 6, 500_000), Ok(()));
+            assert_eq!(nft_token.list_for_sale(token_id + 7, 800_000), Ok(()));
+            set_sender(accounts.bob);
+            let alice_balance = get_balance(accounts.alice);
+            assert_eq!(nft_token.buy(token_id + 7), Err(Error::InsufficientPayment));
+            assert_eq!(nft_token.buy(token_id + 6), Ok(()));
+            assert_eq!(get_balance(accounts.alice), alice_balance + 500_000);
+            assert_eq!(get_balance(accounts.django), django_balance + 1_800_000);
+            // A fee that cannot be paid leaves the token where it is.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_transfer_fee(2_000_000, accounts.django), Ok(()));
+            assert_eq!(
+                nft_token.approve_and_transfer(accounts.bob, token_id + 5),
+                Err(Error::InsufficientPayment)
+            );
+            assert_eq!(
+                nft_token.transfer_and_call(accounts.bob, token_id + 5, Vec::new()),
+                Err(Error::InsufficientPayment)
+            );
+            assert_eq!(nft_token.owner_of(token_id + 5), Some(accounts.alice));
+            assert_eq!(nft_token.owner_of(token_id + 7), Some(accounts.alice));
+            assert_eq!(get_balance(accounts.django), django_balance + 1_800_000);
+            // Neither does a fee that cannot be forwarded.
+            assert_eq!(nft_token.set_transfer_fee(300_000, accounts.django), Ok(()));
+            set_balance(contract_id(), 0);
+            assert_eq!(nft_token.transfer(accounts.bob, token_id + 5), Err(Error::PaymentFailed));
+            assert_eq!(nft_token.owner_of(token_id + 5), Some(accounts.alice));
+        }
+
+        #[ink::test]
+        fn admin_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.lock(token_id, accounts.django), Ok(()));
+            // Only the contract owner can force a transfer.
+            assert_eq!(
+                nft_token.admin_transfer(accounts.bob, accounts.eve, token_id),
+                Err(Error::NotContractOwner)
+            );
+            set_sender(accounts.alice);
+            assert_eq!(
+                nft_token.admin_transfer(accounts.eve, accounts.alice, token_id),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(nft_token.pause(), Ok(()));
+            assert_eq!(nft_token.admin_transfer(accounts.bob, accounts.eve, token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.eve));
+            assert_eq!(nft_token.locker_of(token_id), None);
+            // Both the transfer and the audit record are emitted.
+            let events = recorded_events();
+            assert!(matches!(
+                events[events.len() - 2],
+                Event::Transfer(Transfer { from: Some(from), to: Some(to), id })
+                    if from == accounts.bob && to == accounts.eve && id == token_id
+            ));
+            assert!(matches!(
+                events[events.len() - 1],
+                Event::ForcedTransfer(ForcedTransfer { from, to, id, by })
+                    if from == accounts.bob && to == accounts.eve && id == token_id && by == accounts.alice
+            ));
+        }
+
+        #[ink::test]
+        fn denylist_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.transfer(accounts.bob, token_id + 1), Ok(()));
+            // Only the contract owner can deny accounts.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.set_denied(accounts.eve, true), Err(Error::NotContractOwner));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_denied(accounts.eve, true), Ok(()));
+            assert_eq!(nft_token.set_denied(accounts.bob, true), Ok(()));
+            assert!(nft_token.is_denied(accounts.eve));
+            // Eve can neither receive transfers nor mints.
+            assert_eq!(nft_token.transfer(accounts.eve, token_id), Err(Error::Denied));
+            assert_eq!(nft_token.mint_to(accounts.eve, 1), Err(Error::Denied));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+            // Bob cannot send his token away.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.transfer(accounts.alice, token_id + 1), Err(Error::Denied));
+            // Once allowed again, Eve receives the token.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_denied(accounts.eve, false), Ok(()));
+            assert!(!nft_token.is_denied(accounts.eve));
+            assert_eq!(nft_token.transfer(accounts.eve, token_id), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.eve));
+        }
+
+        #[ink::test]
+        fn denying_operator_revokes_approvals() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.transfer(accounts.bob, token_id + 1), Ok(()));
+            // Alice and Bob make Eve their operator.
+            assert_eq!(nft_token.set_approval_for_all(accounts.eve, true), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.set_approval_for_all(accounts.eve, true), Ok(()));
+            assert_eq!(nft_token.set_approval_for_all(accounts.frank, true), Ok(()));
+            // Denying Eve revokes both approvals, but not Frank's.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_denied(accounts.eve, true), Ok(()));
+            assert!(!nft_token.is_approved_for_all(accounts.alice, accounts.eve));
+            assert!(!nft_token.is_approved_for_all(accounts.bob, accounts.eve));
+            assert!(nft_token.is_approved_for_all(accounts.bob, accounts.frank));
+            // Once allowed again, Eve still cannot move the tokens.
+            assert_eq!(nft_token.set_denied(accounts.eve, false), Ok(()));
+            set_sender(accounts.eve);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.eve, token_id),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(
+                nft_token.transfer_from(accounts.bob, accounts.eve, token_id + 1),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+        }
+
+        #[ink::test]
+        fn batch_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let ids = vec![TOKENID_INIT, TOKENID_INIT + 4, TOKENID_INIT + 9];
+            // Alice transfers three tokens to Bob at once.
+            assert_eq!(nft_token.batch_transfer(accounts.bob, ids.clone()), Ok(()));
+            for id in ids {
+                assert_eq!(nft_token.owner_of(id), Some(accounts.bob));
+            }
+            assert_eq!(nft_token.balance_of(accounts.alice), 7);
+            assert_eq!(nft_token.balance_of(accounts.bob), 3);
+            // One Transfer event per token takes place.
+            assert_eq!(3, recorded_events().len());
+        }
+
+        #[ink::test]
+        fn invalid_batch_transfer_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.transfer(accounts.eve, TOKENID_INIT + 9), Ok(()));
+            // A missing token, a token owned by Eve and a duplicate each roll back the batch.
+            let invalid_batches = vec![
+                (vec![TOKENID_INIT, 2, TOKENID_INIT + 1], Error::TokenNotFound),
+                (vec![TOKENID_INIT, TOKENID_INIT + 9], Error::NotAllowed),
+                (vec![TOKENID_INIT, TOKENID_INIT + 1, TOKENID_INIT], Error::NotAllowed),
+            ];
+            for (ids, error) in invalid_batches {
+                assert_eq!(nft_token.batch_transfer(accounts.bob, ids), Err(error));
+            }
+            // Nothing has been moved.
+            assert_eq!(nft_token.owner_of(TOKENID_INIT), Some(accounts.alice));
+            assert_eq!(nft_token.owner_of(TOKENID_INIT + 1), Some(accounts.alice));
+            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+            assert_eq!(nft_token.balance_of(accounts.bob), 0);
+            assert_eq!(1, recorded_events().len());
+        }
+
+        #[ink::test]
+        fn invalid_transfer_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT + 0;
+            // alice own the token.
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+
+            // token(id=2) not exist
+            assert_eq!(nft_token.owner_of(2), None);
+            // Transfer token fails if it does not exists.
+            assert_eq!(nft_token.transfer(accounts.bob, 2), Err(Error::TokenNotFound));
+
+            set_sender(accounts.bob);
+            // Bob cannot transfer not owned tokens.
+            assert_eq!(nft_token.transfer(accounts.eve, token_id), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn transfer_to_zero_address_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            let zero = AccountId::from(ZERO_ADDRESS);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.transfer(zero, token_id), Err(Error::NotAllowed));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, zero, token_id),
+                Err(Error::NotAllowed)
+            );
+            // Nothing changed.
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            assert_eq!(nft_token.balance_of(accounts.alice), 10);
+            assert_eq!(nft_token.get_approved(token_id), Some(accounts.bob));
+            assert_eq!(nft_token.token_of_owner_by_index(accounts.alice, 0), Some(token_id));
+        }
+
+        #[ink::test]
+        fn is_owner_or_approved_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.set_approval_for_all(accounts.eve, true), Ok(()));
+            // The owner, the approved account and the operator may move the token.
+            assert!(nft_token.is_owner_or_approved(accounts.alice, token_id));
+            assert!(nft_token.is_owner_or_approved(accounts.bob, token_id));
+            assert!(nft_token.is_owner_or_approved(accounts.eve, token_id));
+            // Others may not, and nonexistent tokens cannot be moved.
+            assert!(!nft_token.is_owner_or_approved(accounts.bob, token_id + 1));
+            assert!(!nft_token.is_owner_or_approved(accounts.charlie, token_id));
+            assert!(!nft_token.is_owner_or_approved(accounts.alice, 9999));
+        }
+
+        #[ink::test]
+        fn unchanged_approval_is_not_repeated() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(1, recorded_events().len());
+            // A different deadline is a change.
+            assert_eq!(nft_token.approve_until(accounts.bob, token_id, 1), Ok(()));
+            assert_eq!(2, recorded_events().len());
+        }
+
+        #[ink::test]
+        fn overwrite_approval_mode_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new_with_approval_mode(ApprovalMode::Overwrite);
+            assert_eq!(Simple_NFT::new().approval_mode(), ApprovalMode::Overwrite);
+            let token_id = TOKENID_INIT;
+            // Approving Charlie replaces Bob.
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.approve(accounts.charlie, token_id), Ok(()));
+            assert_eq!(nft_token.get_approvals(token_id), vec![accounts.charlie]);
+            assert!(!nft_token.is_approved(token_id, accounts.bob));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id),
+                Err(Error::NotApproved)
+            );
+        }
+
+        #[ink::test]
+        fn multi_approval_mode_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new_with_approval_mode(ApprovalMode::Multi);
+            assert_eq!(nft_token.approval_mode(), ApprovalMode::Multi);
+            let token_id = TOKENID_INIT;
+            // Approving Charlie keeps Bob approved.
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.approve(accounts.charlie, token_id), Ok(()));
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(2, recorded_events().len());
+            assert_eq!(nft_token.get_approvals(token_id), vec![accounts.bob, accounts.charlie]);
+            assert_eq!(nft_token.get_approved(token_id), Some(accounts.bob));
+            assert!(nft_token.is_approved(token_id, accounts.bob));
+            assert!(nft_token.is_approved(token_id, accounts.charlie));
+            assert_eq!(nft_token.stats().2, 2);
+            // Multi approvals cannot expire.
+            assert_eq!(nft_token.approve_until(accounts.eve, token_id, 1), Err(Error::NotAllowed));
+            // Once Charlie moves the token, none of the approvals is left.
+            set_sender(accounts.charlie);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.charlie, token_id),
+                Ok(())
+            );
+            assert_eq!(nft_token.get_approvals(token_id), Vec::new());
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.charlie, accounts.bob, token_id),
+                Err(Error::NotApproved)
+            );
+            // Revoking clears all approvals at once.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.approve(accounts.bob, token_id + 1), Ok(()));
+            assert_eq!(nft_token.approve(accounts.eve, token_id + 1), Ok(()));
+            assert_eq!(nft_token.revoke_approval(token_id + 1), Ok(()));
+            assert_eq!(nft_token.get_approvals(token_id + 1), Vec::new());
+            assert_eq!(nft_token.stats().2, 0);
+        }
+
+        #[ink::test]
+        fn approve_if_current_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve_if_current(accounts.bob, token_id, None), Ok(()));
+            assert_eq!(nft_token.get_approved(token_id), Some(accounts.bob));
+            // The approval is no longer empty, so it is kept.
+            assert_eq!(
+                nft_token.approve_if_current(accounts.charlie, token_id, None),
+                Err(Error::ApprovalChanged)
+            );
+            assert_eq!(nft_token.get_approved(token_id), Some(accounts.bob));
+            assert_eq!(
+                nft_token.approve_if_current(accounts.charlie, token_id, Some(accounts.bob)),
+                Ok(())
+            );
+            assert_eq!(nft_token.get_approved(token_id), Some(accounts.charlie));
+            // Only the token owner can approve, whatever it expects.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.approve_if_current(accounts.bob, token_id, Some(accounts.charlie)),
+                Err(Error::NotAllowed)
+            );
+        }
+
+        #[ink::test]
+        fn approve_until_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            let deadline = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            assert_eq!(nft_token.approve_until(accounts.bob, token_id, deadline), Ok(()));
+            assert_eq!(nft_token.approve(accounts.bob, token_id + 1), Ok(()));
+            assert_eq!(nft_token.get_approved(token_id), Some(accounts.bob));
+            assert!(nft_token.is_approved(token_id, accounts.bob));
+            // The timed approval lapses once the deadline has passed.
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(nft_token.get_approved(token_id), None);
+            assert!(!nft_token.is_approved(token_id, accounts.bob));
+            assert_eq!(nft_token.get_approved(token_id + 1), Some(accounts.bob));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, token_id + 1),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn batch_approve_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let ids = vec![TOKENID_INIT, TOKENID_INIT + 1, TOKENID_INIT + 2];
+            assert_eq!(nft_token.batch_approve(accounts.bob, ids.clone()), Ok(()));
+            for id in ids {
+                assert_eq!(nft_token.get_approved(id), Some(accounts.bob));
+            }
+            // One Approval event per token takes place.
+            assert_eq!(3, recorded_events().len());
+            // Nothing is approved if any token is not owned by the caller.
+            assert_eq!(nft_token.transfer(accounts.bob, TOKENID_INIT + 4), Ok(()));
+            assert_eq!(
+                nft_token.batch_approve(accounts.eve, vec![TOKENID_INIT + 3, TOKENID_INIT + 4]),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(
+                nft_token.batch_approve(accounts.eve, vec![TOKENID_INIT + 3, 9999]),
+                Err(Error::TokenNotFound)
+            );
+            assert_eq!(nft_token.get_approved(TOKENID_INIT + 3), None);
+        }
+
+        #[ink::test]
+        fn invalid_approve_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Approving a token that does not exist fails instead of trapping.
+            assert_eq!(nft_token.approve(accounts.bob, 9999), Err(Error::TokenNotFound));
+            // Approving the zero address fails.
+            assert_eq!(
+                nft_token.approve(AccountId::from(ZERO_ADDRESS), TOKENID_INIT),
+                Err(Error::NotAllowed)
+            );
+            // Alice cannot approve herself.
+            assert_eq!(nft_token.approve(accounts.alice, TOKENID_INIT), Err(Error::NotAllowed));
+            assert_eq!(nft_token.get_approved(TOKENID_INIT), None);
+            // Bob cannot approve for Alice's token.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.approve(accounts.eve, TOKENID_INIT), Err(Error::NotAllowed));
+            assert_eq!(0, recorded_events().len());
+        }
+
+        #[ink::test]
+        fn approved_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT + 0;
+            // alice has all tokens (10)
+            assert_eq!(nft_token.balance_of(accounts.alice), 10);
+            // Token Id(token_id) is owned by Alice.
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            // Approve token Id(token_id) transfer for Bob on behalf of Alice.
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            // Bob transfers token Id(token_id) from Alice to Eve.
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.eve, token_id),
+                Ok(())
+            );
+            // TokenId Id(token_id) is owned by Eve.
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.eve));
+            // Alice has 9 tokens.
+            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+            // Bob does not owns tokens.
+            assert_eq!(nft_token.balance_of(accounts.bob), 0);
+            // Eve owns 1 token.
+            assert_eq!(nft_token.balance_of(accounts.eve), 1);
+        }
+
+        #[ink::test]
+        fn transfer_with_zero_balance_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Corrupt Alice's counter so that it is zero while she still owns tokens.
+            nft_token.owned_tokens_count.insert(accounts.alice, 0);
+            // The transfer fails instead of wrapping the counter around.
+            assert_eq!(
+                nft_token.transfer(accounts.bob, token_id),
+                Err(Error::BalanceUnderflow)
+            );
+            // Nothing has been moved.
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            assert_eq!(nft_token.balance_of(accounts.alice), 0);
+            assert_eq!(nft_token.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn approved_for_all_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Alice approves Bob for all of her tokens.
+            assert!(!nft_token.is_approved_for_all(accounts.alice, accounts.bob));
+            assert_eq!(nft_token.set_approval_for_all(accounts.bob, true), Ok(()));
+            assert!(nft_token.is_approved_for_all(accounts.alice, accounts.bob));
+            // Bob transfers tokens from Alice to Eve.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.eve, token_id),
+                Ok(())
+            );
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.eve, token_id + 1, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(nft_token.balance_of(accounts.eve), 2);
+            // Bob is not an operator for Eve.
+            assert_eq!(
+                nft_token.transfer_from(accounts.eve, accounts.bob, token_id),
+                Err(Error::NotApproved)
+            );
+            // Once Alice revokes the approval, Bob can no longer move her tokens.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_approval_for_all(accounts.bob, false), Ok(()));
+            assert!(!nft_token.is_approved_for_all(accounts.alice, accounts.bob));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.eve, token_id + 2),
+                Err(Error::NotApproved)
+            );
+        }
+
+        #[ink::test]
+        fn approval_for_all_until_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let deadline = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            assert_eq!(nft_token.operator_expiry(accounts.alice, accounts.bob), None);
+            assert_eq!(nft_token.set_approval_for_all_until(accounts.bob, deadline), Ok(()));
+            assert_eq!(nft_token.set_approval_for_all(accounts.eve, true), Ok(()));
+            assert_eq!(nft_token.operator_expiry(accounts.alice, accounts.bob), Some(deadline));
+            assert_eq!(nft_token.operator_expiry(accounts.alice, accounts.eve), Some(u64::MAX));
+            assert!(nft_token.is_approved_for_all(accounts.alice, accounts.bob));
+            // Bob's approval lapses once the deadline has passed.
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert!(!nft_token.is_approved_for_all(accounts.alice, accounts.bob));
+            assert!(nft_token.is_approved_for_all(accounts.alice, accounts.eve));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.bob, TOKENID_INIT),
+                Err(Error::NotApproved)
+            );
+            // Revoked operators have no expiry.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.set_approval_for_all(accounts.eve, false), Ok(()));
+            assert_eq!(nft_token.operator_expiry(accounts.alice, accounts.eve), None);
+        }
+
+        #[ink::test]
+        fn invalid_approval_for_all_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            // Alice cannot approve herself or the zero address.
+            assert_eq!(
+                nft_token.set_approval_for_all(accounts.alice, true),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(
+                nft_token.set_approval_for_all(AccountId::from(ZERO_ADDRESS), true),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(0, recorded_events().len());
+        }
+
+        #[ink::test]
+        fn approvals_of_owner_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approvals_of_owner(accounts.alice, 0, 10), vec![]);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.approve(accounts.eve, token_id + 2), Ok(()));
+            assert_eq!(
+                nft_token.approvals_of_owner(accounts.alice, 0, 10),
+                vec![(token_id, accounts.bob), (token_id + 2, accounts.eve)]
+            );
+            assert_eq!(
+                nft_token.approvals_of_owner(accounts.alice, 1, 10),
+                vec![(token_id + 2, accounts.eve)]
+            );
+            assert_eq!(
+                nft_token.approvals_of_owner(accounts.alice, 0, 1),
+                vec![(token_id, accounts.bob)]
+            );
+            // Other owners' approvals are not listed.
+            assert_eq!(nft_token.approvals_of_owner(accounts.bob, 0, 10), vec![]);
+            // No more than `MAX_PAGE` pairs are returned at once.
+            assert_eq!(nft_token.mint_range(400, 150), Ok(()));
+            for id in 400..550 {
+                assert_eq!(nft_token.approve(accounts.bob, id), Ok(()));
+            }
+            assert_eq!(
+                nft_token.approvals_of_owner(accounts.alice, 0, 1000).len(),
+                MAX_PAGE as usize
+            );
+        }
+
+        #[ink::test]
+        fn revoke_approval_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            // Only the owner can revoke, not even the approved account.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.revoke_approval(token_id), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.revoke_approval(token_id), Ok(()));
+            assert_eq!(nft_token.get_approved(token_id), None);
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Approval(Approval { from, to, id }))
+                    if *from == accounts.alice && *to == AccountId::from(ZERO_ADDRESS) && *id == token_id
+            ));
+            assert_eq!(nft_token.revoke_approval(1), Err(Error::TokenNotFound));
+        }
+
+        #[ink::test]
+        fn revoke_all_token_approvals_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.approve(accounts.bob, token_id + 1), Ok(()));
+            assert_eq!(nft_token.approve(accounts.eve, token_id + 2), Ok(()));
+            // Bob's own approvals are not affected.
+            assert_eq!(nft_token.transfer(accounts.bob, token_id + 3), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.approve(accounts.eve, token_id + 3), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.revoke_all_token_approvals(), Ok(3));
+            for id in token_id..token_id + 3 {
+                assert_eq!(nft_token.get_approved(id), None);
+            }
+            assert_eq!(nft_token.get_approved(token_id + 3), Some(accounts.eve));
+            // A clearing `Approval` event is emitted per token.
+            let cleared = recorded_events()
+                .into_iter()
+                .filter(|event| {
+                    matches!(event, Event::Approval(Approval { to, .. }) if *to == AccountId::from(ZERO_ADDRESS))
+                })
+                .count();
+            assert_eq!(cleared, 3);
+            // Nothing is left to revoke.
+            assert_eq!(nft_token.revoke_all_token_approvals(), Ok(0));
+        }
+
+        #[ink::test]
+        fn transfer_clears_approval() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.eve, token_id),
+                Ok(())
+            );
+            // The approval is gone for the new owner ...
+            assert_eq!(nft_token.get_approved(token_id), None);
+            assert!(!nft_token.is_approved(token_id, accounts.bob));
+            // ... and no stale entry is left behind for the previous one.
+            assert!(!nft_token.approvals_token.contains_key(&(accounts.alice, token_id)));
+            assert_eq!(nft_token.approvals_token.len(), 0);
+        }
+
+        #[ink::test]
+        fn transfer_emits_approval_clearing_event() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.transfer_from(accounts.alice, accounts.eve, token_id),
+                Ok(())
+            );
+            // The approval is granted, then revoked by the transfer.
+            let approvals = recorded_events()
+                .into_iter()
+                .filter_map(|event| {
+                    match event {
+                        Event::Approval(Approval { from, to, id }) => Some((from, to, id)),
+                        _ => None,
+                    }
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                approvals,
+                vec![
+                    (accounts.alice, accounts.bob, token_id),
+                    (accounts.alice, AccountId::from(ZERO_ADDRESS), token_id),
+                ]
+            );
+            // A transfer without approval emits no clearing event.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.transfer(accounts.bob, token_id + 1), Ok(()));
+            assert_eq!(recorded_events().len(), 4);
+        }
+
+        #[ink::test]
+        fn safe_transfer_to_account_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            // Eve is a plain account, so no receiver check takes place.
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.eve, token_id, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.eve));
+        }
+
+        #[ink::test]
+        fn safe_transfer_to_receiver_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Charlie is a contract accepting tokens.
+            receivers::register(accounts.charlie, receivers::accepting);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.charlie, token_id, vec![1, 2, 3]),
+                Ok(())
+            );
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.charlie));
+            // The receiver was told who sent which token.
+            assert_eq!(
+                receivers::received(),
+                vec![(accounts.bob, accounts.alice, token_id, vec![1, 2, 3])]
+            );
+        }
+
+        #[ink::test]
+        fn safe_transfer_calls_contracts_only() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Django is a contract refusing every token, Eve a plain account.
+            receivers::register(accounts.django, receivers::rejecting);
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.eve, token_id, Vec::new()),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(nft_token.set_approval_for_all(accounts.bob, true), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.eve, token_id, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.eve));
+            // Only asking Django makes the transfer revert.
+            assert_eq!(
+                reverted(|| {
+                    nft_token.safe_transfer_from(accounts.alice, accounts.django, token_id + 1, Vec::new())
+                }),
+                Some(Error::NotReceiver)
+            );
+        }
+
+        #[ink::test]
+        fn safe_transfer_reentrancy_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Charlie is a contract sending the token back while receiving it.
+            receivers::register(accounts.charlie, receivers::reentering);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.charlie, token_id, Vec::new()),
+                Ok(())
+            );
+            // The lock was stored before calling Charlie, so his transfer was refused
+            // and the token moved once.
+            assert_eq!(receivers::reentered(), vec![Err(Error::Reentrancy)]);
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.charlie));
+            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+            assert!(!nft_token.reentrancy_locked);
+        }
+
+        #[ink::test]
+        fn safe_transfer_reentrant_burn_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Charlie is a contract burning the token while receiving it.
+            receivers::register(accounts.charlie, receivers::burning);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.charlie, token_id, Vec::new()),
+                Ok(())
+            );
+            // The burn was refused and Charlie holds the token.
+            assert_eq!(receivers::reentered(), vec![Err(Error::Reentrancy)]);
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.charlie));
+            assert_eq!(nft_token.total_supply(), 10);
+        }
+
+        #[ink::test]
+        fn safe_transfer_calls_receiver_with_final_state() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Frank is a contract looking at the token while receiving it.
+            receivers::register(accounts.frank, receivers::observing);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.safe_transfer_from(accounts.alice, accounts.frank, token_id, Vec::new()),
+                Ok(())
+            );
+            // The token already belonged to Frank, without approval, in the storage
+            // he was called with.
+            assert_eq!(receivers::observed(), vec![(Some(accounts.frank), None, 9)]);
+        }
+
+        #[ink::test]
+        fn transfer_and_call_calls_hook_with_final_state() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Frank is a contract looking at the token while receiving it.
+            receivers::register_hook(accounts.frank, receivers::observing_hook);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.transfer_and_call(accounts.frank, token_id, vec![]), Ok(()));
+            // The token already belonged to Frank, without approval, in the storage
+            // he was called with.
+            assert_eq!(receivers::observed(), vec![(Some(accounts.frank), None, 9)]);
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.frank));
+        }
+
+        #[ink::test]
+        fn safe_transfer_to_non_receiver_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Django is a contract that does not know how to handle tokens.
+            receivers::register(accounts.django, receivers::rejecting);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            // Reverting keeps the token with Alice and Bob approved.
+            assert_eq!(
+                reverted(|| {
+                    nft_token.safe_transfer_from(accounts.alice, accounts.django, token_id, Vec::new())
+                }),
+                Some(Error::NotReceiver)
+            );
+        }
+
+        #[ink::test]
+        fn safe_transfer_reverts_receiver_changes() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Django takes over the royalty and metadata of the token before refusing it.
+            receivers::register(accounts.django, receivers::meddling);
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                reverted(|| {
+                    nft_token.safe_transfer_from(accounts.alice, accounts.django, token_id, Vec::new())
+                }),
+                Some(Error::NotReceiver)
+            );
+            // His changes went through, so only reverting the call undoes them.
+            assert_eq!(receivers::meddled(), vec![Ok(()), Ok(()), Ok(())]);
+        }
+
+        #[ink::test]
+        fn transfer_and_call_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            // Charlie is a staking contract.
+            receivers::register_hook(accounts.charlie, receivers::staking);
+            assert_eq!(nft_token.transfer_and_call(accounts.charlie, token_id, vec![7]), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id), Some(accounts.charlie));
+            assert_eq!(receivers::staked(), vec![(accounts.alice, token_id, vec![7])]);
+            // Plain accounts just receive the token.
+            assert_eq!(nft_token.transfer_and_call(accounts.bob, token_id + 1, vec![]), Ok(()));
+            assert_eq!(nft_token.owner_of(token_id + 1), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn transfer_and_call_to_failing_hook_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            receivers::register_hook(accounts.charlie, receivers::trapping);
+            // Reverting keeps the token with Alice.
+            assert_eq!(
+                reverted(|| nft_token.transfer_and_call(accounts.charlie, token_id, vec![])),
+                Some(Error::NotReceiver)
+            );
+        }
+
+        #[ink::test]
+        fn metadata_of_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let nft_token = Simple_NFT::new();
+            // Every initial token carries its metadata.
+            assert_eq!(nft_token.metadata_of(TOKENID_INIT), Some(MATEDATA_INIT));
+            assert_eq!(nft_token.metadata_of(TOKENID_INIT + 9), Some(MATEDATA_INIT + 9));
+            // Unknown tokens have none.
+            assert_eq!(nft_token.metadata_of(2), None);
+        }
+
+        #[ink::test]
+        fn set_metadata_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.set_metadata(token_id, 42), Ok(()));
+            assert_eq!(nft_token.metadata_of(token_id), Some(42));
+            assert!(matches!(
+                recorded_events()[..],
+                [Event::MetadataUpdate(MetadataUpdate { id })] if id == token_id
+            ));
+            // Bob cannot change the metadata of Alice's token.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.set_metadata(token_id, 7), Err(Error::NotOwner));
+            assert_eq!(nft_token.set_metadata(2, 7), Err(Error::TokenNotFound));
+            assert_eq!(nft_token.metadata_of(token_id), Some(42));
+            // Frozen metadata cannot be changed.
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.freeze_metadata(), Ok(()));
+            assert_eq!(nft_token.set_metadata(token_id, 7), Err(Error::MetadataFrozen));
+        }
+
+        #[ink::test]
+        fn admin_set_metadata_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
+            // Alice corrects the metadata of Bob's token.
+            assert_eq!(nft_token.admin_set_metadata(token_id, 42), Ok(()));
+            assert_eq!(nft_token.metadata_of(token_id), Some(42));
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::MetadataUpdate(MetadataUpdate { id })) if *id == token_id
+            ));
+            assert_eq!(nft_token.admin_set_metadata(2, 42), Err(Error::TokenNotFound));
+            // Bob owns the token but not the contract.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.admin_set_metadata(token_id, 7),
+                Err(Error::NotContractOwner)
+            );
+            assert_eq!(nft_token.metadata_of(token_id), Some(42));
+        }
+
+        #[ink::test]
+        fn attributes_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(
+                nft_token.set_attribute(token_id, "rarity".into(), "legendary".into()),
+                Ok(())
+            );
+            assert_eq!(nft_token.set_attribute(token_id, "color".into(), "red".into()), Ok(()));
+            assert_eq!(
+                nft_token.get_attribute(token_id, "rarity".into()),
+                Some(String::from("legendary"))
+            );
+            assert_eq!(nft_token.get_attribute(token_id, "size".into()), None);
+            assert_eq!(
+                nft_token.attributes_of(token_id, 0, 10),
+                vec![
+                    (String::from("color"), String::from("red")),
+                    (String::from("rarity"), String::from("legendary")),
+                ]
+            );
+            assert_eq!(
+                nft_token.attributes_of(token_id, 1, 10),
+                vec![(String::from("rarity"), String::from("legendary"))]
+            );
+            assert_eq!(nft_token.attributes_of(token_id + 1, 0, 10), vec![]);
+            // Only the token owner can set attributes.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.set_attribute(token_id, "color".into(), "blue".into()),
+                Err(Error::NotOwner)
+            );
+            // Keys and values are limited in length and number.
+            set_sender(accounts.alice);
+            let long = "x".repeat(MAX_ATTRIBUTE_LEN + 1);
+            assert_eq!(
+                nft_token.set_attribute(token_id, long.clone(), "red".into()),
+                Err(Error::NotAllowed)
+            );
+            assert_eq!(nft_token.set_attribute(token_id, "color".into(), long), Err(Error::NotAllowed));
+            for i in 2..MAX_ATTRIBUTES {
+                assert_eq!(nft_token.set_attribute(token_id, i.to_string(), "1".into()), Ok(()));
+            }
+            assert_eq!(
+                nft_token.set_attribute(token_id, "size".into(), "large".into()),
+                Err(Error::NotAllowed)
+            );
+            // Existing attributes can still be changed.
+            assert_eq!(nft_token.set_attribute(token_id, "color".into(), "blue".into()), Ok(()));
+            assert_eq!(nft_token.attributes_of(token_id, 0, 100).len(), MAX_ATTRIBUTES);
+            // Burning the token drops its attributes.
+            assert_eq!(nft_token.burn(token_id), Ok(()));
+            assert_eq!(nft_token.attributes_of(token_id, 0, 10), vec![]);
+            assert_eq!(nft_token.get_attribute(token_id, "color".into()), None);
+        }
+
+        #[ink::test]
+        fn token_metadata_json_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new_with_metadata("TrustNFT".into(), "TNFT".into());
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.token_metadata_json(1), None);
+            assert_eq!(
+                nft_token.token_metadata_json(token_id),
+                Some(String::from(r#"{"name":"TrustNFT #188","image":null,"attributes":[]}"#))
+            );
+            assert_eq!(nft_token.set_token_uri(token_id, "ipfs://a".into()), Ok(()));
+            assert_eq!(
+                nft_token.set_attribute(token_id, "rarity".into(), "legendary".into()),
+                Ok(())
+            );
+            assert_eq!(
+                nft_token.set_attribute(token_id, "motto".into(), "say \"hi\"\n".into()),
+                Ok(())
+            );
+            let json = nft_token.token_metadata_json(token_id).expect("token exists");
+            assert!(json.contains(r#""image":"ipfs://a""#));
+            assert!(json.contains(r#"{"trait_type":"rarity","value":"legendary"}"#));
+            // Values are escaped.
+            assert!(json.contains(r#"{"trait_type":"motto","value":"say \"hi\"\u000a"}"#));
+        }
 
-    /// Unit tests
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
-        use ink_env::{
-            call,
-            test,
-        };
-        use ink_lang as ink;
+        #[ink::test]
+        fn token_info_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
+            assert_eq!(nft_token.set_token_uri(token_id, "ipfs://a".into()), Ok(()));
+            assert_eq!(
+                nft_token.token_info(token_id),
+                TokenInfo {
+                    owner: Some(accounts.alice),
+                    approved: Some(accounts.bob),
+                    metadata: Some(MATEDATA_INIT),
+                    uri: Some(String::from("ipfs://a")),
+                }
+            );
+            assert_eq!(
+                nft_token.token_info(1),
+                TokenInfo {
+                    owner: None,
+                    approved: None,
+                    metadata: None,
+                    uri: None,
+                }
+            );
+        }
 
         #[ink::test]
-        fn init_works() {
+        fn token_by_metadata_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             set_sender(accounts.alice);
             // Create a new contract instance.
             let mut nft_token = Simple_NFT::new();
-            // Token 1 does not exists.
-            assert_eq!(nft_token.owner_of(1), None);
-            // Alice has owns tokens.
-            assert_eq!(nft_token.balance_of(accounts.alice), 10);
-            for i in 0..10 {
-                assert_eq!(nft_token.owner_of(TOKENID_INIT+i), Some(accounts.alice));
-            }
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.token_by_metadata(MATEDATA_INIT), Some(token_id));
+            assert_eq!(nft_token.token_by_metadata(42), None);
+            // A changed value no longer points to the token.
+            assert_eq!(nft_token.set_metadata(token_id, 42), Ok(()));
+            assert_eq!(nft_token.token_by_metadata(42), Some(token_id));
+            assert_eq!(nft_token.token_by_metadata(MATEDATA_INIT), None);
+            // The last token a shared value is set on wins.
+            assert_eq!(nft_token.set_metadata(token_id + 1, 42), Ok(()));
+            assert_eq!(nft_token.token_by_metadata(42), Some(token_id + 1));
+            assert_eq!(nft_token.burn(token_id + 1), Ok(()));
+            assert_eq!(nft_token.token_by_metadata(42), None);
         }
 
         #[ink::test]
-        fn transfer_works() {
+        fn freeze_token_metadata_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             set_sender(accounts.alice);
             // Create a new contract instance.
             let mut nft_token = Simple_NFT::new();
-            let token_id = TOKENID_INIT + 0;
-            // Alice owns all tokens
-            assert_eq!(nft_token.balance_of(accounts.alice), 10);
-            // Bob does not owns any token
-            assert_eq!(nft_token.balance_of(accounts.bob), 0);
-            // alice own the token
-            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
-            // The first Transfer event takes place
-            assert_eq!(0, ink_env::test::recorded_events().count());
-            // Alice transfers token 1 to Bob
-            assert_eq!(nft_token.transfer(accounts.bob, token_id), Ok(()));
-            // The second Transfer event takes place
-            assert_eq!(1, ink_env::test::recorded_events().count());
-            // bob own the token
-            assert_eq!(nft_token.owner_of(token_id), Some(accounts.bob));
-            // Bob owns token 1
-            assert_eq!(nft_token.balance_of(accounts.bob), 1);
-            assert_eq!(nft_token.balance_of(accounts.alice), 9);
+            let token_id = TOKENID_INIT;
+            // Only the token owner can freeze it.
+            set_sender(accounts.bob);
+            assert_eq!(nft_token.freeze_token_metadata(token_id), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(nft_token.freeze_token_metadata(token_id), Ok(()));
+            assert!(nft_token.token_metadata_frozen(token_id));
+            assert_eq!(nft_token.set_metadata(token_id, 42), Err(Error::MetadataFrozen));
+            assert_eq!(
+                nft_token.set_token_uri(token_id, "ipfs://a".into()),
+                Err(Error::MetadataFrozen)
+            );
+            assert_eq!(
+                nft_token.set_token_uris(token_id, vec!["ipfs://a".into(), "ipfs://b".into()]),
+                Err(Error::MetadataFrozen)
+            );
+            assert_eq!(
+                nft_token.set_attribute(token_id, "rarity".into(), "legendary".into()),
+                Err(Error::MetadataFrozen)
+            );
+            assert_eq!(nft_token.metadata_of(token_id), Some(MATEDATA_INIT));
+            // Other tokens remain editable.
+            assert!(!nft_token.token_metadata_frozen(token_id + 1));
+            assert_eq!(nft_token.set_metadata(token_id + 1, 42), Ok(()));
+            assert_eq!(nft_token.set_token_uri(token_id + 1, "ipfs://b".into()), Ok(()));
         }
 
         #[ink::test]
-        fn invalid_transfer_should_fail() {
+        fn set_token_uri_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             set_sender(accounts.alice);
             // Create a new contract instance.
             let mut nft_token = Simple_NFT::new();
-            let token_id = TOKENID_INIT + 0;
-            // alice own the token.
-            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
+            let token_id = TOKENID_INIT;
+            let uri = String::from("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+            // No URI has been set yet.
+            assert_eq!(nft_token.token_uri(token_id), None);
+            // Alice sets the URI of her token.
+            assert_eq!(nft_token.set_token_uri(token_id, uri.clone()), Ok(()));
+            assert_eq!(nft_token.token_uri(token_id), Some(uri));
+            // The numeric metadata is left untouched.
+            assert_eq!(nft_token.metadata_of(token_id), Some(MATEDATA_INIT));
+        }
 
-            // token(id=2) not exist
-            assert_eq!(nft_token.owner_of(2), None);
-            // Transfer token fails if it does not exists.
-            assert_eq!(nft_token.transfer(accounts.bob, 2), Err(Error::TokenNotFound));
+        #[ink::test]
+        fn set_token_uri_normalizes_uri() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert!(validate_uri("ipfs://a"));
+            assert!(!validate_uri(" \t\n"));
+            // Valid URIs are stored as they are.
+            assert_eq!(nft_token.set_token_uri(token_id, String::from("ipfs://a")), Ok(()));
+            assert_eq!(nft_token.token_uri(token_id), Some(String::from("ipfs://a")));
+            // Surrounding whitespace is trimmed.
+            assert_eq!(nft_token.set_token_uri(token_id, String::from("  ipfs://b\n")), Ok(()));
+            assert_eq!(nft_token.token_uri(token_id), Some(String::from("ipfs://b")));
+            // Blank URIs are rejected.
+            assert_eq!(nft_token.set_token_uri(token_id, String::new()), Err(Error::InvalidUri));
+            assert_eq!(
+                nft_token.set_token_uris(token_id, vec![String::from("ipfs://c"), String::from(" ")]),
+                Err(Error::InvalidUri)
+            );
+            assert_eq!(nft_token.set_base_uri(String::from(" ")), Err(Error::InvalidUri));
+            assert_eq!(nft_token.token_uri(token_id), Some(String::from("ipfs://b")));
+        }
+
+        #[ink::test]
+        fn set_token_uri_emits_metadata_update() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.set_token_uri(token_id, String::from("ipfs://a")), Ok(()));
+            let events = recorded_events();
+            assert_eq!(events.len(), 1);
+            assert!(matches!(
+                events[0],
+                Event::MetadataUpdate(MetadataUpdate { id }) if id == token_id
+            ));
+        }
+
+        #[ink::test]
+        fn metadata_update_topics_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            assert_eq!(nft_token.set_token_uri(token_id, String::from("ipfs://a")), Ok(()));
+            assert_eq!(nft_token.set_metadata(token_id, 1), Ok(()));
+            assert_eq!(nft_token.set_metadata(token_id + 1, 1), Ok(()));
+            assert_eq!(nft_token.set_token_uris(token_id, vec![String::from("ipfs://b")]), Ok(()));
+            assert_eq!(nft_token.set_approval_for_all(accounts.bob, true), Ok(()));
+            let events = ink_env::test::recorded_events().skip(INIT_EVENTS).collect::<Vec<_>>();
+            assert!(matches!(
+                recorded_events()[..3],
+                [
+                    Event::MetadataUpdate(MetadataUpdate { id: first }),
+                    Event::MetadataUpdate(MetadataUpdate { id: second }),
+                    Event::MetadataUpdate(MetadataUpdate { id: third }),
+                ] if first == token_id && second == token_id && third == token_id + 1
+            ));
+            // `BatchMetadataUpdate` has no topic fields to compare the others against.
+            let base = events[3].topics.len();
+            // Updates are indexed by token.
+            assert_eq!(events[0].topics.len(), base + 1);
+            assert_eq!(events[0].topics, events[1].topics);
+            assert_ne!(events[1].topics, events[2].topics);
+            // Operator approvals are indexed by owner and operator.
+            assert_eq!(events[4].topics.len(), base + 2);
+        }
+
+        #[ink::test]
+        fn set_token_uris_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            let token_id = TOKENID_INIT;
+            let uris = vec![String::from("ipfs://a"), String::from("ipfs://b")];
+            assert_eq!(nft_token.set_token_uris(token_id, uris), Ok(()));
+            assert_eq!(nft_token.token_uri(token_id), Some(String::from("ipfs://a")));
+            assert_eq!(nft_token.token_uri(token_id + 1), Some(String::from("ipfs://b")));
+            let events = recorded_events();
+            assert_eq!(events.len(), 1);
+            assert!(matches!(
+                events[0],
+                Event::BatchMetadataUpdate(BatchMetadataUpdate { from_id, to_id })
+                    if from_id == token_id && to_id == token_id + 1
+            ));
+            // Nothing is set if any token in the range is not owned by the caller.
+            assert_eq!(nft_token.transfer(accounts.bob, token_id + 3), Ok(()));
+            let uris = vec![String::from("ipfs://c"); 4];
+            assert_eq!(nft_token.set_token_uris(token_id + 2, uris), Err(Error::NotOwner));
+            assert_eq!(nft_token.token_uri(token_id + 2), None);
+            // An empty batch is rejected.
+            assert_eq!(nft_token.set_token_uris(token_id, Vec::new()), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn base_uri_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.base_uri(), None);
+            assert_eq!(nft_token.set_base_uri(String::from("https://api.x/")), Ok(()));
+            assert_eq!(nft_token.token_uri(188), Some(String::from("https://api.x/188")));
+            // A token's own URI takes precedence over the base URI.
+            assert_eq!(nft_token.set_token_uri(189, String::from("ipfs://a")), Ok(()));
+            assert_eq!(nft_token.token_uri(189), Some(String::from("ipfs://a")));
+            // Tokens that do not exist have no URI.
+            assert_eq!(nft_token.token_uri(2), None);
+            // Only the contract owner can set the base URI.
+            set_sender(accounts.bob);
+            assert_eq!(
+                nft_token.set_base_uri(String::from("https://bob/")),
+                Err(Error::NotContractOwner)
+            );
+            assert_eq!(nft_token.base_uri(), Some(String::from("https://api.x/")));
+        }
 
+        #[ink::test]
+        fn freeze_metadata_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            set_sender(accounts.alice);
+            // Create a new contract instance.
+            let mut nft_token = Simple_NFT::new();
+            assert_eq!(nft_token.set_base_uri(String::from("https://api.x/")), Ok(()));
+            // Only the contract owner can freeze the metadata.
             set_sender(accounts.bob);
-            // Bob cannot transfer not owned tokens.
-            assert_eq!(nft_token.transfer(accounts.eve, token_id), Err(Error::NotAllowed));
+            assert_eq!(nft_token.freeze_metadata(), Err(Error::NotContractOwner));
+            set_sender(accounts.alice);
+            assert!(!nft_token.metadata_frozen());
+            assert_eq!(nft_token.freeze_metadata(), Ok(()));
+            assert!(nft_token.metadata_frozen());
+            // No URI can be changed afterwards.
+            assert_eq!(
+                nft_token.set_base_uri(String::from("https://api.y/")),
+                Err(Error::MetadataFrozen)
+            );
+            assert_eq!(
+                nft_token.set_token_uri(TOKENID_INIT, String::from("ipfs://a")),
+                Err(Error::MetadataFrozen)
+            );
+            assert_eq!(
+                nft_token.set_token_uris(TOKENID_INIT, vec![String::from("ipfs://a")]),
+                Err(Error::MetadataFrozen)
+            );
+            assert_eq!(nft_token.token_uri(188), Some(String::from("https://api.x/188")));
         }
 
         #[ink::test]
-        fn approved_transfer_works() {
+        fn set_token_uri_fails_for_non_owner() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             set_sender(accounts.alice);
             // Create a new contract instance.
             let mut nft_token = Simple_NFT::new();
-            let token_id = TOKENID_INIT + 0;
-            // alice has all tokens (10)
-            assert_eq!(nft_token.balance_of(accounts.alice), 10);
-            // Token Id(token_id) is owned by Alice.
-            assert_eq!(nft_token.owner_of(token_id), Some(accounts.alice));
-            // Approve token Id(token_id) transfer for Bob on behalf of Alice.
-            assert_eq!(nft_token.approve(accounts.bob, token_id), Ok(()));
             set_sender(accounts.bob);
-            // Bob transfers token Id(token_id) from Alice to Eve.
+            // Bob cannot set the URI of Alice's token.
             assert_eq!(
-                nft_token.transfer_from(accounts.alice, accounts.eve, token_id),
-                Ok(())
+                nft_token.set_token_uri(TOKENID_INIT, String::from("ipfs://bob")),
+                Err(Error::NotOwner)
             );
-            // TokenId Id(token_id) is owned by Eve.
-            assert_eq!(nft_token.owner_of(token_id), Some(accounts.eve));
-            // Alice has 9 tokens.
-            assert_eq!(nft_token.balance_of(accounts.alice), 9);
-            // Bob does not owns tokens.
-            assert_eq!(nft_token.balance_of(accounts.bob), 0);
-            // Eve owns 1 token.
-            assert_eq!(nft_token.balance_of(accounts.eve), 1);
+            // Nor of a token that does not exist.
+            assert_eq!(
+                nft_token.set_token_uri(2, String::from("ipfs://bob")),
+                Err(Error::TokenNotFound)
+            );
+            assert_eq!(nft_token.token_uri(TOKENID_INIT), None);
+        }
+
+        /// Storage access counts of the transfer paths.
+        ///
+        /// The off-chain environment only touches contract storage when the contract is
+        /// pulled from or pushed to it, so every run loads a collection of 100 tokens
+        /// from storage, transfers some of them and writes the result back.
+        mod bench {
+            use super::*;
+            use ink_primitives::Key;
+            use ink_storage::traits::{pull_spread_root, push_spread_root};
+
+            /// Returns the storage reads and writes spent on `transfers` sequential transfers.
+            fn measure(transfers: u32) -> (usize, usize) {
+                let accounts =
+                    ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                        .expect("Cannot get accounts");
+                set_sender(accounts.alice);
+                let root = Key::from([0x00; 32]);
+                push_spread_root(&Simple_NFT::new_with_count(100), &root);
+                let mut nft_token: Simple_NFT = pull_spread_root(&root);
+                let (reads, writes) = storage_rw();
+                for i in 0..transfers {
+                    assert_eq!(nft_token.transfer(accounts.bob, TOKENID_INIT + i), Ok(()));
+                }
+                push_spread_root(&nft_token, &root);
+                let (all_reads, all_writes) = storage_rw();
+                (all_reads - reads, all_writes - writes)
+            }
+
+            fn storage_rw() -> (usize, usize) {
+                ink_env::test::get_contract_storage_rw::<ink_env::DefaultEnvironment>(&contract_id())
+                    .expect("Cannot get storage reads and writes")
+            }
+
+            #[ink::test]
+            fn transfer_storage_access_is_bounded() {
+                // Writing the contract back costs 49 writes even without any transfer.
+                assert_eq!(measure(0), (0, 49));
+                // Baselines: (reads, writes) must not grow beyond these.
+                for &(transfers, (max_reads, max_writes)) in
+                    &[(1, (20, 61)), (10, (164, 142)), (100, (1404, 752))]
+                {
+                    let (reads, writes) = measure(transfers);
+                    assert!(reads <= max_reads, "{} transfers read {} cells", transfers, reads);
+                    assert!(writes <= max_writes, "{} transfers wrote {} cells", transfers, writes);
+                }
+            }
+
+            #[ink::test]
+            fn transfer_storage_access_is_linear() {
+                // A hundred transfers never cost more than a hundred single ones.
+                let (_, base_writes) = measure(0);
+                let (reads, writes) = measure(1);
+                let (many_reads, many_writes) = measure(100);
+                assert!(many_reads <= reads * 100);
+                assert!(many_writes - base_writes <= (writes - base_writes) * 100);
+            }
+        }
+
+        /// Mock receiver contracts for the off-chain environment.
+        pub(super) mod receivers {
+            use super::*;
+            use core::mem::ManuallyDrop;
+            use ink_primitives::Key;
+            use ink_storage::traits::{pull_spread_root, push_spread_root};
+            use std::{
+                cell::RefCell,
+                collections::HashMap,
+            };
+
+            /// A mock `on_erc721_received(operator, from, id, data)` implementation.
+            pub type Receiver =
+                fn(&mut Simple_NFT, AccountId, AccountId, TokenId, Vec<u8>) -> [u8; 4];
+
+            /// The `(operator, from, id, data)` arguments of a receiver call.
+            pub type Received = (AccountId, AccountId, TokenId, Vec<u8>);
+
+            /// The `(owner, approved, sender balance)` the `observing` receiver saw.
+            pub type Observed = (Option<AccountId>, Option<AccountId>, u32);
+
+            /// A mock `on_token_transfer(from, id, data)` implementation.
+            pub type Hook = fn(&mut Simple_NFT, AccountId, TokenId, Vec<u8>) -> ink_env::Result<()>;
+
+            thread_local! {
+                static RECEIVERS: RefCell<HashMap<AccountId, Receiver>> = RefCell::new(HashMap::new());
+                static RECEIVED: RefCell<Vec<Received>> = const { RefCell::new(Vec::new()) };
+                static REENTERED: RefCell<Vec<Result<(), Error>>> = const { RefCell::new(Vec::new()) };
+                static OBSERVED: RefCell<Vec<Observed>> = const { RefCell::new(Vec::new()) };
+                static MEDDLED: RefCell<Vec<Result<(), Error>>> = const { RefCell::new(Vec::new()) };
+                static HOOKS: RefCell<HashMap<AccountId, Hook>> = RefCell::new(HashMap::new());
+                static STAKED: RefCell<Vec<(AccountId, TokenId, Vec<u8>)>> = const { RefCell::new(Vec::new()) };
+            }
+
+            /// Turns `account` into a contract implementing `on_erc721_received` with `receiver`.
+            pub fn register(account: AccountId, receiver: Receiver) {
+                RECEIVERS.with(|receivers| receivers.borrow_mut().insert(account, receiver));
+            }
+
+            /// Returns all calls the `accepting` receiver has seen so far.
+            pub fn received() -> Vec<Received> {
+                RECEIVED.with(|received| received.borrow().clone())
+            }
+
+            /// Accepts every token and records the call.
+            pub fn accepting(
+                _contract: &mut Simple_NFT,
+                operator: AccountId,
+                from: AccountId,
+                id: TokenId,
+                data: Vec<u8>,
+            ) -> [u8; 4] {
+                RECEIVED.with(|received| received.borrow_mut().push((operator, from, id, data)));
+                ON_ERC721_RECEIVED
+            }
+
+            /// Returns the `(owner, approved, sender balance)` the `observing` receiver has seen.
+            pub fn observed() -> Vec<Observed> {
+                OBSERVED.with(|observed| observed.borrow().clone())
+            }
+
+            /// Accepts every token and records the state of the contract while being called.
+            pub fn observing(
+                contract: &mut Simple_NFT,
+                _operator: AccountId,
+                from: AccountId,
+                id: TokenId,
+                _data: Vec<u8>,
+            ) -> [u8; 4] {
+                let state = (contract.owner_of(id), contract.get_approved(id), contract.balance_of(from));
+                OBSERVED.with(|observed| observed.borrow_mut().push(state));
+                ON_ERC721_RECEIVED
+            }
+
+            /// Records the state of the contract while being called, like `observing`.
+            pub fn observing_hook(
+                contract: &mut Simple_NFT,
+                from: AccountId,
+                id: TokenId,
+                _data: Vec<u8>,
+            ) -> ink_env::Result<()> {
+                let state = (contract.owner_of(id), contract.get_approved(id), contract.balance_of(from));
+                OBSERVED.with(|observed| observed.borrow_mut().push(state));
+                Ok(())
+            }
+
+            /// Returns the results of the calls made back by the `reentering` receiver.
+            pub fn reentered() -> Vec<Result<(), Error>> {
+                REENTERED.with(|reentered| reentered.borrow().clone())
+            }
+
+            /// Tries to send the token it is receiving back to its previous owner.
+            pub fn reentering(
+                contract: &mut Simple_NFT,
+                _operator: AccountId,
+                from: AccountId,
+                id: TokenId,
+                _data: Vec<u8>,
+            ) -> [u8; 4] {
+                let result = contract.transfer(from, id);
+                REENTERED.with(|reentered| reentered.borrow_mut().push(result));
+                ON_ERC721_RECEIVED
+            }
+
+            /// Returns the results of the changes the `meddling` receiver made.
+            pub fn meddled() -> Vec<Result<(), Error>> {
+                MEDDLED.with(|meddled| meddled.borrow().clone())
+            }
+
+            /// Takes over the royalty of the token it is receiving, changes its URI and
+            /// freezes its metadata, then refuses it.
+            pub fn meddling(
+                contract: &mut Simple_NFT,
+                _operator: AccountId,
+                _from: AccountId,
+                id: TokenId,
+                _data: Vec<u8>,
+            ) -> [u8; 4] {
+                let receiver = ink_env::caller::<Environment>().expect("Cannot get caller");
+                let results = vec![
+                    contract.set_token_royalty(id, receiver, ROYALTY_DENOMINATOR),
+                    contract.set_token_uri(id, String::from("ipfs://meddled")),
+                    contract.freeze_token_metadata(id),
+                ];
+                MEDDLED.with(|meddled| meddled.borrow_mut().extend(results));
+                [0x00; 4]
+            }
+
+            /// Tries to burn the token it is receiving.
+            pub fn burning(
+                contract: &mut Simple_NFT,
+                _operator: AccountId,
+                _from: AccountId,
+                id: TokenId,
+                _data: Vec<u8>,
+            ) -> [u8; 4] {
+                let result = contract.burn(id);
+                REENTERED.with(|reentered| reentered.borrow_mut().push(result));
+                ON_ERC721_RECEIVED
+            }
+
+            /// Answers with something other than the magic value.
+            pub fn rejecting(
+                _contract: &mut Simple_NFT,
+                _operator: AccountId,
+                _from: AccountId,
+                _id: TokenId,
+                _data: Vec<u8>,
+            ) -> [u8; 4] {
+                [0x00; 4]
+            }
+
+            /// Turns `account` into a contract implementing `on_token_transfer` with `hook`.
+            pub fn register_hook(account: AccountId, hook: Hook) {
+                HOOKS.with(|hooks| hooks.borrow_mut().insert(account, hook));
+            }
+
+            /// Returns all calls the `staking` hook has seen so far.
+            pub fn staked() -> Vec<(AccountId, TokenId, Vec<u8>)> {
+                STAKED.with(|staked| staked.borrow().clone())
+            }
+
+            /// Records the call, like a staking contract crediting the sender.
+            pub fn staking(
+                _contract: &mut Simple_NFT,
+                from: AccountId,
+                id: TokenId,
+                data: Vec<u8>,
+            ) -> ink_env::Result<()> {
+                STAKED.with(|staked| staked.borrow_mut().push((from, id, data)));
+                Ok(())
+            }
+
+            /// Traps like a contract refusing the token.
+            pub fn trapping(
+                _contract: &mut Simple_NFT,
+                _from: AccountId,
+                _id: TokenId,
+                _data: Vec<u8>,
+            ) -> ink_env::Result<()> {
+                Err(ink_env::Error::CalleeTrapped)
+            }
+
+            /// Runs `call` as contract `account` calling into the token contract.
+            ///
+            /// Like the real dispatch, the token contract is loaded from storage for the
+            /// call and written back afterwards, so the mocks only see what the calling
+            /// message wrote to storage before calling them.
+            fn dispatch<R>(account: AccountId, call: impl FnOnce(&mut Simple_NFT) -> R) -> R {
+                let root = Key::from([0x00; 32]);
+                test::push_execution_context::<Environment>(
+                    account,
+                    contract_id(),
+                    1000000,
+                    0,
+                    test::CallData::new(call::Selector::new([0x00; 4])), // dummy
+                );
+                // Never dropped, as dropping it would clear its storage cells.
+                let mut contract = ManuallyDrop::new(pull_spread_root::<Simple_NFT>(&root));
+                let result = call(&mut contract);
+                push_spread_root(&*contract, &root);
+                test::pop_execution_context();
+                result
+            }
+
+            /// Dispatches to the hook registered for `to`.
+            ///
+            /// Accounts without a registered hook behave like plain accounts.
+            pub fn on_token_transfer(
+                from: AccountId,
+                to: AccountId,
+                id: TokenId,
+                data: Vec<u8>,
+            ) -> ink_env::Result<()> {
+                let hook = HOOKS.with(|hooks| hooks.borrow().get(&to).cloned());
+                match hook {
+                    Some(hook) => dispatch(to, |contract| hook(contract, from, id, data)),
+                    None => Err(ink_env::Error::NotCallable),
+                }
+            }
+
+            /// Dispatches to the receiver registered for `to`.
+            ///
+            /// Accounts without a registered receiver behave like plain accounts.
+            pub fn on_erc721_received(
+                operator: AccountId,
+                from: AccountId,
+                to: AccountId,
+                id: TokenId,
+                data: Vec<u8>,
+            ) -> ink_env::Result<[u8; 4]> {
+                let receiver = RECEIVERS.with(|receivers| receivers.borrow().get(&to).cloned());
+                match receiver {
+                    Some(receiver) => {
+                        Ok(dispatch(to, |contract| receiver(contract, operator, from, id, data)))
+                    }
+                    None => Err(ink_env::Error::NotCallable),
+                }
+            }
+        }
+
+        /// Mock NFT contracts of other collections for the off-chain environment.
+        pub(super) mod foreign {
+            use super::*;
+            use std::{
+                cell::RefCell,
+                collections::HashMap,
+            };
+
+            thread_local! {
+                static HOLDINGS: RefCell<HashMap<(AccountId, TokenId), AccountId>> = RefCell::new(HashMap::new());
+            }
+
+            /// Turns `nft_contract` into an NFT contract where `owner` holds token `id`.
+            pub fn register(nft_contract: AccountId, id: TokenId, owner: AccountId) {
+                HOLDINGS.with(|holdings| holdings.borrow_mut().insert((nft_contract, id), owner));
+            }
+
+            /// Returns the owner of token `id` of `nft_contract`.
+            pub fn owner_of(nft_contract: AccountId, id: TokenId) -> Option<AccountId> {
+                HOLDINGS.with(|holdings| holdings.borrow().get(&(nft_contract, id)).cloned())
+            }
+
+            /// Transfers token `id` of `nft_contract` from the calling contract to `to`.
+            ///
+            /// Accounts without a registered token behave like plain accounts.
+            pub fn transfer(
+                nft_contract: AccountId,
+                to: AccountId,
+                id: TokenId,
+            ) -> ink_env::Result<Result<(), Error>> {
+                match owner_of(nft_contract, id) {
+                    None => Err(ink_env::Error::NotCallable),
+                    Some(owner) if owner != contract_id() => Ok(Err(Error::NotOwner)),
+                    Some(_) => {
+                        register(nft_contract, id, to);
+                        Ok(Ok(()))
+                    }
+                }
+            }
+        }
+
+        fn contract_id() -> AccountId {
+            ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract id")
+        }
+
+        fn set_balance(account: AccountId, balance: Balance) {
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(account, balance)
+                .expect("Cannot set account balance");
+        }
+
+        fn get_balance(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        /// Returns the error `call` reverted with, or `None` if it returned.
+        ///
+        /// On-chain the runtime then discards every change the call made, which the
+        /// off-chain environment cannot do, so the contract must not be used afterwards.
+        fn reverted<R>(call: impl FnOnce() -> R) -> Option<Error> {
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(call))
+                .err()
+                .map(|payload| *payload.downcast::<Error>().expect("Call panicked instead of reverting"))
         }
 
         fn set_sender(sender: AccountId) {
             let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
-                .unwrap_or([0x0; 32].into());
+                .unwrap_or(ZERO_ADDRESS.into());
             test::push_execution_context::<Environment>(
                 sender,
                 callee,